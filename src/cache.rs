@@ -0,0 +1,175 @@
+use crate::time::unix_time;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// 검색 결과는 새 비디오가 올라오므로 짧게 둔다.
+pub const TTL_SEARCH: Duration = Duration::from_secs(6 * 60 * 60);
+// 조회수, 좋아요 수가 바뀌므로 짧게 둔다.
+pub const TTL_VIDEOS: Duration = Duration::from_secs(60 * 60);
+pub const TTL_CHANNELS: Duration = Duration::from_secs(24 * 60 * 60);
+pub const TTL_COMMENTS: Duration = Duration::from_secs(6 * 60 * 60);
+// 시청 페이지의 자막 주소는 몇 시간 뒤에 만료된다.
+pub const TTL_WATCH_PAGE: Duration = Duration::from_secs(60 * 60);
+pub const TTL_TRANSCRIPT: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+pub const TTL_TRANSLATION: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+// 같은 프로세스 안에서 임시 파일 이름이 겹치지 않게 한다.
+static TEMPORARY_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Entry as written on disk.
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    /// unix time in seconds
+    stored_at: u64,
+    /// key before hashing, to tell what the file holds
+    key: String,
+    value: T,
+}
+
+/// Content-addressed cache on disk. Each entry is a JSON file named after the
+/// sha256 of its key, under a directory per namespace. Cache failures never
+/// fail the caller: unreadable entries are misses and writes are best effort.
+#[derive(Debug, Clone, Default)]
+pub struct Cache {
+    /// `None` when caching is disabled
+    dir: Option<PathBuf>,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+        }
+    }
+
+    /// cache that never stores anything.
+    pub fn disabled() -> Self {
+        Self { dir: None }
+    }
+
+    /// cache in `CACHE_DIR`, `.cache` by default.
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("CACHE_DIR").unwrap_or_else(|_| ".cache".to_owned()))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// key made of several parts, so that `("a b", "c")` and `("a", "b c")`
+    /// don't collide.
+    pub fn key(parts: &[&str]) -> String {
+        parts.join("\u{1f}")
+    }
+
+    /// value stored under `key` less than `ttl` ago.
+    pub fn get<T: DeserializeOwned>(&self, namespace: &str, key: &str, ttl: Duration) -> Option<T> {
+        let path = self.path(namespace, key)?;
+        let text = std::fs::read_to_string(path).ok()?;
+        let entry: Entry<T> = serde_json::from_str(&text).ok()?;
+        (unix_time() < entry.stored_at.saturating_add(ttl.as_secs())).then_some(entry.value)
+    }
+
+    /// stores `value` under `key`, replacing any previous value.
+    pub fn put<T: Serialize>(&self, namespace: &str, key: &str, value: &T) {
+        if let Some(path) = self.path(namespace, key) {
+            let entry = Entry {
+                stored_at: unix_time(),
+                key: key.to_owned(),
+                value,
+            };
+            let _ = write(&path, &entry);
+        }
+    }
+
+    /// value stored under `key` less than `ttl` ago, or the result of `f`
+    /// which is stored if it succeeds.
+    pub async fn get_or_fetch<T, E, F, Fut>(
+        &self,
+        namespace: &str,
+        key: &str,
+        ttl: Duration,
+        f: F,
+    ) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if let Some(value) = self.get(namespace, key, ttl) {
+            return Ok(value);
+        }
+        let value = f().await?;
+        self.put(namespace, key, &value);
+        Ok(value)
+    }
+
+    /// what `parse` reads from the value stored under `key` less than `ttl`
+    /// ago, or from the result of `f`, which is stored only once `parse`
+    /// reads it. Keeps error pages and the like out of the cache.
+    pub async fn get_or_fetch_parsed<T, U, E, F, Fut>(
+        &self,
+        namespace: &str,
+        key: &str,
+        ttl: Duration,
+        f: F,
+        parse: impl Fn(&T) -> Result<U, E>,
+    ) -> Result<U, E>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        // 읽지 못하는 값이 캐시돼 있으면 다시 가져온다.
+        if let Some(parsed) = self
+            .get(namespace, key, ttl)
+            .and_then(|value| parse(&value).ok())
+        {
+            return Ok(parsed);
+        }
+        let value = f().await?;
+        let parsed = parse(&value)?;
+        self.put(namespace, key, &value);
+        Ok(parsed)
+    }
+
+    /// removes every entry.
+    pub fn clear(&self) -> std::io::Result<()> {
+        match &self.dir {
+            Some(dir) if dir.exists() => std::fs::remove_dir_all(dir),
+            _ => Ok(()),
+        }
+    }
+
+    fn path(&self, namespace: &str, key: &str) -> Option<PathBuf> {
+        let hash = format!("{:x}", Sha256::digest(key.as_bytes()));
+        Some(
+            self.dir
+                .as_ref()?
+                .join(namespace)
+                .join(&hash[..2])
+                .join(format!("{}.json", hash)),
+        )
+    }
+}
+
+/// writes through a temporary file so that concurrent readers never see a
+/// partial entry.
+fn write<T: Serialize>(path: &Path, entry: &Entry<T>) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        TEMPORARY_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&temporary, serde_json::to_vec(entry)?)?;
+    std::fs::rename(temporary, path)?;
+    Ok(())
+}
@@ -0,0 +1,302 @@
+use crate::cache::Cache;
+use crate::error::{Error, Result};
+use crate::rate_limit::RateLimiter;
+use crate::translate::cached::CachedTranslator;
+use crate::translate::translator::Translator;
+use crate::youtube_api::client::YoutubeApiClient;
+use crate::youtube_transcript::config::{CaptionSource, Config, InnerTubeConfig};
+use crate::youtube_transcript::parser::Caption;
+use crate::youtube_transcript::youtube::YoutubeBuilder;
+use axum::routing::{get, post};
+use axum::Router;
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn cache(name: &str) -> Cache {
+    let dir =
+        std::env::temp_dir().join(format!("youtube_recommend_{}_{}", name, std::process::id()));
+    let cache = Cache::new(dir);
+    cache.clear().unwrap();
+    cache
+}
+
+/// Translator upper-casing the texts and recording what it was sent.
+#[derive(Default)]
+struct UpperTranslator {
+    sent: Arc<Mutex<Vec<String>>>,
+}
+
+impl Translator for UpperTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        _source: &'a str,
+        _target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        self.sent.lock().unwrap().extend(texts.iter().cloned());
+        Box::pin(async move { Ok(texts.iter().map(|x| x.to_uppercase()).collect()) })
+    }
+}
+
+#[tokio::test]
+async fn cache_get_or_fetch_test() {
+    let cache = cache("get_or_fetch");
+    let key = Cache::key(&["search", "갤럭시"]);
+    assert_ne!(key, Cache::key(&["search 갤럭시"]));
+
+    let fetched = cache
+        .get_or_fetch("search", &key, DAY, || async {
+            Ok::<_, ()>("first".to_owned())
+        })
+        .await;
+    assert_eq!(fetched.as_deref(), Ok("first"));
+
+    let cached = cache
+        .get_or_fetch("search", &key, DAY, || async {
+            Ok::<_, ()>("second".to_owned())
+        })
+        .await;
+    assert_eq!(cached.as_deref(), Ok("first"));
+
+    // 만료된 값은 없는 것과 같다.
+    assert_eq!(cache.get::<String>("search", &key, Duration::ZERO), None);
+    // 실패한 결과는 저장하지 않는다.
+    let failed = cache
+        .get_or_fetch("search", "other", DAY, || async {
+            Err::<String, _>("quota")
+        })
+        .await;
+    assert_eq!(failed, Err("quota"));
+    assert_eq!(cache.get::<String>("search", "other", DAY), None);
+
+    let disabled = Cache::disabled();
+    disabled.put("search", &key, &"value");
+    assert_eq!(disabled.get::<String>("search", &key, DAY), None);
+
+    cache.clear().unwrap();
+}
+
+#[tokio::test]
+async fn cache_translator_test() {
+    let cache = cache("translator");
+    let inner = UpperTranslator::default();
+    let sent = inner.sent.clone();
+    let translator = CachedTranslator::new(Box::new(inner), cache.clone(), "upper");
+
+    let texts = vec!["a".to_owned(), "b".to_owned()];
+    let translations = translator.translate(&texts, "KO", "EN").await.unwrap();
+    assert_eq!(translations, vec!["A", "B"]);
+
+    let texts = vec!["b".to_owned(), "c".to_owned(), "a".to_owned()];
+    let translations = translator.translate(&texts, "KO", "EN").await.unwrap();
+    assert_eq!(translations, vec!["B", "C", "A"]);
+    assert_eq!(*sent.lock().unwrap(), vec!["a", "b", "c"]);
+
+    // 언어 쌍이 다르면 다시 번역한다.
+    translator.translate_one("a", "EN", "KO").await.unwrap();
+    assert_eq!(sent.lock().unwrap().len(), 4);
+
+    cache.clear().unwrap();
+}
+
+#[tokio::test]
+async fn cache_youtube_api_test() {
+    // 처음에는 200 으로 html 오류 페이지를 돌려주는 api
+    let requests = Arc::new(AtomicUsize::new(0));
+    let router = Router::new().route(
+        "/search",
+        get({
+            let requests = requests.clone();
+            move || async move {
+                match requests.fetch_add(1, Ordering::SeqCst) {
+                    0 => "<html>잠시 후 다시 시도하세요</html>".to_owned(),
+                    _ => r#"{"items": [{"id": {"videoId": "abc"}}]}"#.to_owned(),
+                }
+            }
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let cache = cache("youtube_api");
+    let youtube_api = YoutubeApiClient::new("test-key")
+        .with_base_url(format!("http://{}", addr))
+        .with_cache(cache.clone());
+
+    // 읽지 못한 응답은 캐시하지 않는다.
+    assert!(youtube_api.search("갤럭시", 1).await.is_err());
+    let results = youtube_api.search("갤럭시", 1).await.unwrap();
+    assert_eq!(results[0].video_id(), Some("abc"));
+    assert!(youtube_api.search("갤럭시", 1).await.is_ok());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    cache.clear().unwrap();
+}
+
+#[tokio::test]
+async fn cache_player_test() {
+    // 처음에는 로그인이 필요하다고 답하는 플레이어 api
+    let requests = Arc::new(AtomicUsize::new(0));
+    let router = Router::new().route(
+        "/youtubei/v1/player",
+        post({
+            let requests = requests.clone();
+            move || async move {
+                match requests.fetch_add(1, Ordering::SeqCst) {
+                    0 => r#"{"playabilityStatus": {"status": "LOGIN_REQUIRED"}}"#,
+                    _ => {
+                        r#"{"playabilityStatus": {"status": "OK"}, "captions": {"playerCaptionsTracklistRenderer": {"captionTracks": [{"baseUrl": "/api/timedtext?v=abc&lang=ko", "languageCode": "ko"}]}}}"#
+                    }
+                }
+            }
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let cache = cache("player");
+    let other_client = Config {
+        innertube: InnerTubeConfig {
+            client_name: "WEB",
+            client_version: "2.20250101",
+        },
+        ..Config::default()
+    };
+    let caption_tracks = |config: &'static Config| {
+        let builder = YoutubeBuilder::default()
+            .config(config)
+            .cache(cache.clone())
+            .rate_limiter(RateLimiter::unlimited())
+            .base_url(format!("http://{}", addr));
+        async move {
+            builder
+                .build()
+                .player_caption_tracks("abc")
+                .await
+                .map(|x| x.len())
+        }
+    };
+    let default: &'static Config = Box::leak(Box::default());
+    let other_client: &'static Config = Box::leak(Box::new(other_client));
+
+    // 재생할 수 없다는 응답은 캐시하지 않는다.
+    assert!(caption_tracks(default).await.is_err());
+    assert_eq!(caption_tracks(default).await.unwrap(), 1);
+    assert_eq!(caption_tracks(default).await.unwrap(), 1);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    // 다른 클라이언트의 응답은 따로 캐시한다.
+    assert_eq!(caption_tracks(other_client).await.unwrap(), 1);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    cache.clear().unwrap();
+}
+
+#[tokio::test]
+async fn cache_watch_page_test() {
+    // 처음에는 동의 페이지와 빈 자막을 돌려주는 사이트
+    let (pages, tracks) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let router = Router::new()
+        .route(
+            "/watch",
+            get({
+                let pages = pages.clone();
+                move || async move {
+                    match pages.fetch_add(1, Ordering::SeqCst) {
+                        0 => "<html>Before you continue to YouTube</html>",
+                        _ => {
+                            r#"<html><script>var ytInitialPlayerResponse = {"playabilityStatus":{"status":"OK"},"captions":{"playerCaptionsTracklistRenderer":{"captionTracks":[{"baseUrl":"/api/timedtext?v=abc&lang=ko","languageCode":"ko"}]}},"videoDetails":{}};</script></html>"#
+                        }
+                    }
+                }
+            }),
+        )
+        .route(
+            "/api/timedtext",
+            get({
+                let tracks = tracks.clone();
+                move || async move {
+                    match tracks.fetch_add(1, Ordering::SeqCst) {
+                        0 => "",
+                        _ => r#"<transcript><text start="0" dur="1">안녕하세요</text></transcript>"#,
+                    }
+                }
+            }),
+        );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let cache = cache("watch_page");
+    let html: &'static Config =
+        Box::leak(Box::new(Config::default().with_source(CaptionSource::Html)));
+    let builder = YoutubeBuilder::default()
+        .config(html)
+        .cache(cache.clone())
+        .rate_limiter(RateLimiter::unlimited())
+        .base_url(format!("http://{}", addr));
+    let youtube = builder.build();
+    let url = youtube.watch_url("abc");
+
+    // 자막 목록을 읽지 못한 페이지는 캐시하지 않는다.
+    assert!(youtube.caption_tracks(&url).await.is_err());
+    assert_eq!(youtube.caption_tracks(&url).await.unwrap().len(), 1);
+    assert_eq!(youtube.caption_tracks(&url).await.unwrap().len(), 1);
+    assert_eq!(pages.load(Ordering::SeqCst), 2);
+
+    // 읽지 못한 자막도 캐시하지 않고, 형식마다 따로 캐시한다.
+    let caption = |fmt: &str| Caption {
+        base_url: format!("http://{}/api/timedtext?v=abc&lang=ko{}", addr, fmt),
+        lang_code: "ko".to_owned(),
+        ..Default::default()
+    };
+    assert!(youtube.transcript_from_caption(&caption("")).await.is_err());
+    for fmt in ["", "", "&fmt=srv3"] {
+        let transcript = youtube
+            .transcript_from_caption(&caption(fmt))
+            .await
+            .unwrap();
+        assert_eq!(transcript.transcripts[0].text, "안녕하세요");
+    }
+    assert_eq!(tracks.load(Ordering::SeqCst), 3);
+
+    cache.clear().unwrap();
+}
+
+/// Translator answering one translation fewer than it was sent.
+struct ShortTranslator;
+
+impl Translator for ShortTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        _source: &'a str,
+        _target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(async move { Ok(texts[1..].to_vec()) })
+    }
+}
+
+#[tokio::test]
+async fn cache_translator_short_test() {
+    let cache = cache("translator_short");
+    let translator = CachedTranslator::new(Box::new(ShortTranslator), cache.clone(), "short");
+
+    // 빠진 번역을 빈 문자열로 채우지 않는다.
+    let texts = ["배터리".to_owned(), "카메라".to_owned()];
+    assert!(matches!(
+        translator.translate(&texts, "KO", "EN").await,
+        Err(Error::TranslationFailed(_))
+    ));
+    assert!(cache
+        .get::<String>("translate-short", &Cache::key(&["KO", "EN", "카메라"]), DAY)
+        .is_none());
+
+    cache.clear().unwrap();
+}
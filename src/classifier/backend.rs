@@ -0,0 +1,35 @@
+use crate::error::{Error, Result};
+use std::str::FromStr;
+
+/// Models the classifiers run, picked with `CLASSIFIER`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClassifierBackend {
+    /// rust-bert models, downloaded on first use
+    #[default]
+    Bert,
+    /// deterministic models of [`crate::classifier::fake`], for tests and
+    /// offline runs
+    Fake,
+}
+
+impl FromStr for ClassifierBackend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "bert" | "rust-bert" => Ok(ClassifierBackend::Bert),
+            "fake" => Ok(ClassifierBackend::Fake),
+            _ => Err(Error::ModelLoadFailed(format!("unknown classifier: {}", s))),
+        }
+    }
+}
+
+impl ClassifierBackend {
+    /// reads the backend from `CLASSIFIER`, [`ClassifierBackend::Bert`] by default.
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("CLASSIFIER") {
+            Ok(backend) => backend.parse(),
+            Err(_) => Ok(ClassifierBackend::default()),
+        }
+    }
+}
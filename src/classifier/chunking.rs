@@ -0,0 +1,37 @@
+use std::ops::Range;
+
+/// Estimated tokens of a chunk. The question answering model reads 384 tokens
+/// with the question and the summarization model 1024, the rest is cut off.
+pub const MAX_CHUNK_TOKENS: usize = 320;
+
+/// rough number of model tokens of `text`: a token per 4 ascii characters of
+/// a word, plus one per other character as a korean syllable rarely
+/// translates into more than a token.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|word| {
+            let ascii = word.chars().filter(char::is_ascii).count();
+            ascii.div_ceil(4) + word.chars().count() - ascii
+        })
+        .sum()
+}
+
+/// packs consecutive texts into chunks of at most `max_tokens` estimated
+/// tokens, never splitting a text. A text longer than `max_tokens` is a
+/// chunk of its own.
+pub fn chunks(texts: &[&str], max_tokens: usize) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let (mut start, mut tokens) = (0, 0);
+    for (i, text) in texts.iter().enumerate() {
+        let text_tokens = estimate_tokens(text);
+        if i > start && tokens + text_tokens > max_tokens {
+            chunks.push(start..i);
+            (start, tokens) = (i, 0);
+        }
+        tokens += text_tokens;
+    }
+    if start < texts.len() {
+        chunks.push(start..texts.len());
+    }
+    chunks
+}
@@ -0,0 +1,96 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::entity_extraction_classifier::EntityExtractionClassifier;
+use crate::classifier::keyword_extraction_classifier::KeywordExtractionClassifier;
+use crate::classifier::question_answering_classifier::QuestionAnsweringClassifier;
+use crate::classifier::sentiment_classifier::SentimentClassifier;
+use crate::classifier::summarization_classifier::SummarizationClassifier;
+use anyhow::Result;
+use tokio::task::JoinHandle;
+
+/// Spawns every classifier once and hands out cloned handles to them, so the
+/// models are loaded a single time per run
+pub struct ClassifierHub {
+    question_answering: QuestionAnsweringClassifier,
+    summarization: SummarizationClassifier,
+    sentiment: SentimentClassifier,
+    keyword_extraction: KeywordExtractionClassifier,
+    entity_extraction: EntityExtractionClassifier,
+    handles: Vec<JoinHandle<Result<()>>>,
+}
+
+impl ClassifierHub {
+    /// Spawn all classifiers on separate threads, running the models of `backend`
+    pub fn spawn(backend: ClassifierBackend) -> Self {
+        let (question_answering_handle, question_answering) =
+            QuestionAnsweringClassifier::spawn(backend);
+        let (summarization_handle, summarization) = SummarizationClassifier::spawn(backend);
+        let (sentiment_handle, sentiment) = SentimentClassifier::spawn(backend);
+        let (keyword_extraction_handle, keyword_extraction) =
+            KeywordExtractionClassifier::spawn(backend);
+        let (entity_extraction_handle, entity_extraction) =
+            EntityExtractionClassifier::spawn(backend);
+
+        Self {
+            question_answering,
+            summarization,
+            sentiment,
+            keyword_extraction,
+            entity_extraction,
+            handles: vec![
+                question_answering_handle,
+                summarization_handle,
+                sentiment_handle,
+                keyword_extraction_handle,
+                entity_extraction_handle,
+            ],
+        }
+    }
+
+    pub fn question_answering(&self) -> QuestionAnsweringClassifier {
+        self.question_answering.clone()
+    }
+
+    pub fn summarization(&self) -> SummarizationClassifier {
+        self.summarization.clone()
+    }
+
+    pub fn sentiment(&self) -> SentimentClassifier {
+        self.sentiment.clone()
+    }
+
+    pub fn keyword_extraction(&self) -> KeywordExtractionClassifier {
+        self.keyword_extraction.clone()
+    }
+
+    pub fn entity_extraction(&self) -> EntityExtractionClassifier {
+        self.entity_extraction.clone()
+    }
+
+    /// Close the channels and wait for the runners to exit. Runners only stop
+    /// once every cloned handle is dropped, so drop them before calling this.
+    /// The hub drops its own handles before waiting, a runner would otherwise
+    /// never see its channel close
+    pub async fn shutdown(self) -> Result<()> {
+        let Self {
+            question_answering,
+            summarization,
+            sentiment,
+            keyword_extraction,
+            entity_extraction,
+            handles,
+        } = self;
+        drop((
+            question_answering,
+            summarization,
+            sentiment,
+            keyword_extraction,
+            entity_extraction,
+        ));
+
+        for handle in handles {
+            handle.await??;
+        }
+
+        Ok(())
+    }
+}
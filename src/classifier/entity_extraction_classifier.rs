@@ -0,0 +1,82 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::fake::CapitalizedEntities;
+use crate::classifier::model_worker::{ModelWorker, Pipeline};
+use anyhow::Result;
+use rust_bert::pipelines::ner::NERModel;
+use rust_bert::pipelines::token_classification::TokenClassificationConfig;
+use tokio::task::JoinHandle;
+
+/// Named entity, without the offset rust-bert also returns
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entity {
+    pub word: String,
+    pub score: f64,
+    /// Ex: "PER", "ORG", "LOC", "MISC"
+    pub label: String,
+}
+
+/// Model finding the named entities of english texts, one list per text
+pub trait EntityExtractor {
+    fn extract(&self, texts: &[String]) -> Result<Vec<Vec<Entity>>>;
+}
+
+impl EntityExtractor for NERModel {
+    fn extract(&self, texts: &[String]) -> Result<Vec<Vec<Entity>>> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        Ok(self
+            .predict_full_entities(&texts)
+            .into_iter()
+            .map(|entities| {
+                entities
+                    .into_iter()
+                    .map(|x| Entity {
+                        word: x.word,
+                        score: x.score,
+                        label: x.label,
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+impl Pipeline for Box<dyn EntityExtractor> {
+    type Config = ClassifierBackend;
+    type Input = Vec<String>;
+    type Output = Vec<Vec<Entity>>;
+
+    fn load(backend: Self::Config) -> Result<Self> {
+        Ok(match backend {
+            ClassifierBackend::Bert => {
+                Box::new(NERModel::new(TokenClassificationConfig::default())?)
+            }
+            ClassifierBackend::Fake => Box::new(CapitalizedEntities),
+        })
+    }
+
+    fn run(&self, texts: Self::Input) -> Result<Self::Output> {
+        self.extract(&texts)
+    }
+}
+
+/// Runner for named entity recognition
+#[derive(Debug, Clone)]
+pub struct EntityExtractionClassifier {
+    worker: ModelWorker<Box<dyn EntityExtractor>>,
+}
+
+impl EntityExtractionClassifier {
+    /// Spawn a classifier on a separate thread and return a classifier instance
+    /// to interact with it
+    pub fn spawn(
+        backend: ClassifierBackend,
+    ) -> (JoinHandle<Result<()>>, EntityExtractionClassifier) {
+        let (handle, worker) = ModelWorker::spawn(backend);
+        (handle, EntityExtractionClassifier { worker })
+    }
+
+    /// Make the runner predict a sample and return the result
+    pub async fn predict(&self, texts: Vec<String>) -> Result<Vec<Vec<Entity>>> {
+        self.worker.request(texts).await
+    }
+}
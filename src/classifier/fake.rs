@@ -0,0 +1,257 @@
+// rust-bert 모델 대신 쓰는 결정적인 모델들. 바로 불러와지고 아무것도 내려받지 않아서
+// 네트워크 없이 파이프라인을 테스트할 수 있다.
+
+use crate::classifier::entity_extraction_classifier::{Entity, EntityExtractor};
+use crate::classifier::keyword_extraction_classifier::{Keyword, KeywordExtractor};
+use crate::classifier::question_answering_classifier::QuestionAnswerer;
+use crate::classifier::sentiment_classifier::SentimentPredictor;
+use crate::classifier::summarization_classifier::Summarizer;
+use crate::recommend::aspect;
+use anyhow::Result;
+use rust_bert::pipelines::question_answering::Answer;
+use rust_bert::pipelines::sentiment::{Sentiment, SentimentPolarity};
+use std::collections::{HashMap, HashSet};
+
+// 영어 단어는 그 단어나 규칙 굴절형일 때, 한국어는 어미가 붙으므로 어간으로 시작할 때
+// 긍정, 부정으로 센다.
+const POSITIVE: &[&str] = &[
+    "good",
+    "great",
+    "best",
+    "love",
+    "like",
+    "excellent",
+    "amazing",
+    "awesome",
+    "nice",
+    "perfect",
+    "recommend",
+    "fast",
+    "long",
+    "좋",
+    "최고",
+    "추천",
+    "만족",
+    "훌륭",
+    "빠르",
+];
+const NEGATIVE: &[&str] = &[
+    "bad",
+    "worst",
+    "hate",
+    "poor",
+    "terrible",
+    "awful",
+    "disappoint",
+    "disappointment",
+    "slow",
+    "hot",
+    "expensive",
+    "broke",
+    "broken",
+    "not",
+    "별로",
+    "최악",
+    "아쉽",
+    "느리",
+    "비싸",
+    "발열",
+    "실망",
+];
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "i",
+    "in", "is", "it", "its", "me", "my", "of", "on", "or", "so", "that", "the", "this", "to",
+    "was", "we", "with", "you", "your",
+];
+// 텍스트마다 돌려주는 키워드 수
+const MAX_KEYWORDS: usize = 5;
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|x: char| !x.is_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split_inclusive(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+}
+
+/// whether `word` counts as the lexicon entry `term`. Ex: "loved" for "love",
+/// "좋아요" for "좋", but not "note" for "not" nor "hotel" for "hot"
+fn counts(word: &str, term: &str) -> bool {
+    if term.is_ascii() {
+        aspect::inflects(word, term)
+    } else {
+        word.starts_with(term)
+    }
+}
+
+/// Counts the words of a lexicon. Negative when there are more negative words
+/// than positive ones, the score growing with the difference.
+pub struct LexiconSentiment;
+
+impl SentimentPredictor for LexiconSentiment {
+    fn predict(&self, texts: &[String]) -> Result<Vec<Sentiment>> {
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let count = |lexicon: &[&str]| {
+                    words(text)
+                        .filter(|word| lexicon.iter().any(|x| counts(word, x)))
+                        .count()
+                };
+                let (positive, negative) = (count(POSITIVE), count(NEGATIVE));
+                let polarity = if negative > positive {
+                    SentimentPolarity::Negative
+                } else {
+                    SentimentPolarity::Positive
+                };
+                let difference = positive.abs_diff(negative) as f64;
+                Sentiment {
+                    polarity,
+                    score: 0.5 + 0.5 * difference / (difference + 1.0),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Keeps the first sentence of each text.
+pub struct LeadSummarizer;
+
+impl Summarizer for LeadSummarizer {
+    fn summarize(&self, texts: &[String]) -> Result<Vec<String>> {
+        Ok(texts
+            .iter()
+            .map(|text| sentences(text).next().unwrap_or_default().to_owned())
+            .collect())
+    }
+}
+
+/// Answers with the sentence of the context sharing the most words with the
+/// question, the first one on ties.
+pub struct OverlapQuestionAnswering;
+
+impl QuestionAnswerer for OverlapQuestionAnswering {
+    fn answer(&self, question: String, context: String) -> Result<Vec<Vec<Answer>>> {
+        let question: HashSet<String> = words(&question)
+            .filter(|x| !STOP_WORDS.contains(&x.as_str()))
+            .collect();
+
+        let mut best: Option<(usize, &str)> = None;
+        for sentence in sentences(&context) {
+            let overlap = words(sentence).filter(|x| question.contains(x)).count();
+            if best.is_none_or(|(most, _)| overlap > most) {
+                best = Some((overlap, sentence));
+            }
+        }
+
+        let answers = best
+            .map(|(overlap, sentence)| {
+                let start = context[..context.find(sentence).unwrap_or_default()]
+                    .chars()
+                    .count();
+                Answer {
+                    score: (overlap as f64 + 1.0) / (question.len() as f64 + 1.0),
+                    start,
+                    end: start + sentence.chars().count(),
+                    answer: sentence.to_owned(),
+                }
+            })
+            .into_iter()
+            .collect();
+        Ok(vec![answers])
+    }
+}
+
+/// Scores the words of each text by tf-idf over the texts of the request.
+pub struct TfIdfKeywords;
+
+impl KeywordExtractor for TfIdfKeywords {
+    fn extract(&self, texts: &[String]) -> Result<Vec<Vec<Keyword>>> {
+        let documents: Vec<Vec<String>> = texts
+            .iter()
+            .map(|text| {
+                words(text)
+                    .filter(|x| x.chars().count() > 1 && !STOP_WORDS.contains(&x.as_str()))
+                    .collect()
+            })
+            .collect();
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for document in &documents {
+            for word in document.iter().collect::<HashSet<_>>() {
+                *document_frequency.entry(word).or_default() += 1;
+            }
+        }
+
+        Ok(documents
+            .iter()
+            .map(|document| {
+                let mut frequency: HashMap<&str, usize> = HashMap::new();
+                for word in document {
+                    *frequency.entry(word).or_default() += 1;
+                }
+                let mut keywords: Vec<Keyword> = frequency
+                    .into_iter()
+                    .map(|(word, count)| {
+                        let idf = ((1.0 + documents.len() as f32)
+                            / (1.0 + document_frequency[word] as f32))
+                            .ln()
+                            + 1.0;
+                        Keyword {
+                            text: word.to_owned(),
+                            score: count as f32 / document.len() as f32 * idf,
+                        }
+                    })
+                    .collect();
+                keywords.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.text.cmp(&b.text)));
+                keywords.truncate(MAX_KEYWORDS);
+                keywords
+            })
+            .collect())
+    }
+}
+
+/// Takes runs of capitalized words holding a model number, or of at least two
+/// capitalized words, as products. Ex: "Galaxy S23", "Pixel Buds"
+pub struct CapitalizedEntities;
+
+impl EntityExtractor for CapitalizedEntities {
+    fn extract(&self, texts: &[String]) -> Result<Vec<Vec<Entity>>> {
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let mut entities = Vec::new();
+                let mut run: Vec<&str> = Vec::new();
+                let tokens = text.split(|x: char| !x.is_alphanumeric());
+                for token in tokens.chain(std::iter::once("")) {
+                    if is_capitalized(token) {
+                        run.push(token);
+                        continue;
+                    }
+                    let has_number = run.iter().any(|x| x.chars().any(|x| x.is_ascii_digit()));
+                    if has_number || run.len() > 1 {
+                        entities.push(Entity {
+                            word: run.join(" "),
+                            score: 0.9,
+                            label: "MISC".to_owned(),
+                        });
+                    }
+                    run.clear();
+                }
+                entities
+            })
+            .collect())
+    }
+}
+
+/// "Galaxy", "S23", "15" but not "the" nor "The" starting a sentence.
+fn is_capitalized(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|x| x.is_ascii_uppercase() || x.is_ascii_digit())
+        && !STOP_WORDS.contains(&token.to_lowercase().as_str())
+}
@@ -1,48 +1,78 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::fake::TfIdfKeywords;
+use crate::classifier::model_worker::{ModelWorker, Pipeline};
 use anyhow::Result;
-use rust_bert::pipelines::keywords_extraction::{
-    Keyword, KeywordExtractionConfig, KeywordExtractionModel,
-};
-use std::sync::mpsc;
-use tokio::sync::oneshot;
-use tokio::task::{self, JoinHandle};
-
-/// Message type for internal channel, passing around texts and return value
-/// senders
-type Message = (Vec<String>, oneshot::Sender<Vec<Vec<Keyword>>>);
-
-/// Runner for sentiment classification
+use rust_bert::pipelines::keywords_extraction::{KeywordExtractionConfig, KeywordExtractionModel};
+use tokio::task::JoinHandle;
+
+/// Keyword of a text, without the offsets rust-bert also returns
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword {
+    pub text: String,
+    pub score: f32,
+}
+
+/// Model extracting the keywords of english texts, one list per text
+pub trait KeywordExtractor {
+    fn extract(&self, texts: &[String]) -> Result<Vec<Vec<Keyword>>>;
+}
+
+impl KeywordExtractor for KeywordExtractionModel<'static> {
+    fn extract(&self, texts: &[String]) -> Result<Vec<Vec<Keyword>>> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        Ok(self
+            .predict(&texts)?
+            .into_iter()
+            .map(|keywords| {
+                keywords
+                    .into_iter()
+                    .map(|x| Keyword {
+                        text: x.text,
+                        score: x.score,
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+impl Pipeline for Box<dyn KeywordExtractor> {
+    type Config = ClassifierBackend;
+    type Input = Vec<String>;
+    type Output = Vec<Vec<Keyword>>;
+
+    fn load(backend: Self::Config) -> Result<Self> {
+        Ok(match backend {
+            ClassifierBackend::Bert => Box::new(KeywordExtractionModel::new(
+                KeywordExtractionConfig::default(),
+            )?),
+            ClassifierBackend::Fake => Box::new(TfIdfKeywords),
+        })
+    }
+
+    fn run(&self, texts: Self::Input) -> Result<Self::Output> {
+        self.extract(&texts)
+    }
+}
+
+/// Runner for keyword extraction
 #[derive(Debug, Clone)]
 pub struct KeywordExtractionClassifier {
-    sender: mpsc::SyncSender<Message>,
+    worker: ModelWorker<Box<dyn KeywordExtractor>>,
 }
 
 impl KeywordExtractionClassifier {
     /// Spawn a classifier on a separate thread and return a classifier instance
     /// to interact with it
-    pub fn spawn() -> (JoinHandle<Result<()>>, KeywordExtractionClassifier) {
-        let (sender, receiver) = mpsc::sync_channel(100);
-        let handle = task::spawn_blocking(move || Self::runner(receiver));
-        (handle, KeywordExtractionClassifier { sender })
-    }
-
-    /// The classification runner itself
-    fn runner(receiver: mpsc::Receiver<Message>) -> Result<()> {
-        // Needs to be in sync runtime, async doesn't work
-        let model = KeywordExtractionModel::new(KeywordExtractionConfig::default())?;
-
-        while let Ok((texts, sender)) = receiver.recv() {
-            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
-            let keywords = model.predict(&texts)?;
-            sender.send(keywords).expect("sending results");
-        }
-
-        Ok(())
+    pub fn spawn(
+        backend: ClassifierBackend,
+    ) -> (JoinHandle<Result<()>>, KeywordExtractionClassifier) {
+        let (handle, worker) = ModelWorker::spawn(backend);
+        (handle, KeywordExtractionClassifier { worker })
     }
 
     /// Make the runner predict a sample and return the result
     pub async fn predict(&self, texts: Vec<String>) -> Result<Vec<Vec<Keyword>>> {
-        let (sender, receiver) = oneshot::channel();
-        self.sender.send((texts, sender))?;
-        Ok(receiver.await?)
+        self.worker.request(texts).await
     }
 }
@@ -1,4 +1,10 @@
+pub mod backend;
+pub mod chunking;
+pub mod classifier_hub;
+pub mod entity_extraction_classifier;
+pub mod fake;
 pub mod keyword_extraction_classifier;
+pub mod model_worker;
 pub mod question_answering_classifier;
 pub mod sentiment_classifier;
 pub mod summarization_classifier;
@@ -0,0 +1,112 @@
+use crate::error::Error;
+use anyhow::{anyhow, Result};
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio::task::{self, JoinHandle};
+
+/// A model that can be driven by a [`ModelWorker`]. Implementing this for a
+/// rust-bert pipeline is all that is needed to run it on its own thread
+pub trait Pipeline: Sized + 'static {
+    /// Configuration the model is loaded with
+    type Config: Send + 'static;
+    /// Request sent to the model
+    type Input: Send + 'static;
+    /// Result returned by the model
+    type Output: Send + 'static;
+
+    /// Load the model, called once on the worker thread
+    fn load(config: Self::Config) -> Result<Self>;
+
+    /// Run the model on a single request
+    fn run(&self, input: Self::Input) -> Result<Self::Output>;
+}
+
+/// Message type for internal channel, passing around the request and return
+/// value senders
+type Message<M> = (
+    <M as Pipeline>::Input,
+    oneshot::Sender<Result<<M as Pipeline>::Output>>,
+);
+
+/// Handle to a model running on a separate thread
+pub struct ModelWorker<M: Pipeline> {
+    sender: mpsc::SyncSender<Message<M>>,
+}
+
+impl<M: Pipeline> ModelWorker<M> {
+    /// Spawn the model on a separate thread and return a worker instance to
+    /// interact with it
+    pub fn spawn(config: M::Config) -> (JoinHandle<Result<()>>, ModelWorker<M>) {
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let handle = task::spawn_blocking(move || Self::runner(config, receiver, None));
+        (handle, ModelWorker { sender })
+    }
+
+    /// Spawn the model like [`ModelWorker::spawn`] and wait until it is
+    /// loaded, failing with [`Error::ModelLoadFailed`] if it can't be
+    pub async fn spawn_loaded(
+        config: M::Config,
+    ) -> Result<(JoinHandle<Result<()>>, ModelWorker<M>)> {
+        let (sender, receiver) = mpsc::sync_channel(100);
+        let (loaded_sender, loaded) = oneshot::channel();
+        let handle =
+            task::spawn_blocking(move || Self::runner(config, receiver, Some(loaded_sender)));
+        if loaded.await.is_err() {
+            // 모델을 불러오지 못한 runner 는 그 오류로 끝난다.
+            handle.await??;
+            return Err(anyhow!("model worker has stopped"));
+        }
+        Ok((handle, ModelWorker { sender }))
+    }
+
+    /// The model runner itself, telling `loaded` once the model is loaded
+    fn runner(
+        config: M::Config,
+        receiver: mpsc::Receiver<Message<M>>,
+        loaded: Option<oneshot::Sender<()>>,
+    ) -> Result<()> {
+        // Needs to be in sync runtime, async doesn't work
+        let model = M::load(config).map_err(|e| Error::ModelLoadFailed(e.to_string()))?;
+        if let Some(loaded) = loaded {
+            let _ = loaded.send(());
+        }
+
+        while let Ok((input, sender)) = receiver.recv() {
+            // the requester is gone if sending fails, nothing left to do then
+            let _ = sender.send(model.run(input));
+        }
+
+        Ok(())
+    }
+
+    /// Make the runner process a request and return the result. The future is
+    /// spelled out as `Send` so that callers holding a model with a lifetime,
+    /// such as `KeywordExtractionModel<'static>`, can be spawned on the runtime
+    pub fn request(&self, input: M::Input) -> impl Future<Output = Result<M::Output>> + Send {
+        let (sender, receiver) = oneshot::channel();
+        let sent = self
+            .sender
+            .send((input, sender))
+            .map_err(|_| anyhow!("model worker has stopped"));
+        async move {
+            sent?;
+            receiver.await?
+        }
+    }
+}
+
+impl<M: Pipeline> Clone for ModelWorker<M> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<M: Pipeline> Debug for ModelWorker<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModelWorker").finish_non_exhaustive()
+    }
+}
@@ -1,47 +1,80 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::fake::OverlapQuestionAnswering;
+use crate::classifier::model_worker::{ModelWorker, Pipeline};
 use anyhow::Result;
 use rust_bert::pipelines::question_answering::{
     Answer, QaInput, QuestionAnsweringConfig, QuestionAnsweringModel,
 };
-use std::sync::mpsc;
-use tokio::sync::oneshot;
-use tokio::task::{self, JoinHandle};
+use tokio::task::JoinHandle;
 
-/// Message type for internal channel, passing around texts and return value
-/// senders
-type Message = (String, String, oneshot::Sender<Vec<Vec<Answer>>>);
+/// Model answering a question from an english context
+pub trait QuestionAnswerer {
+    /// best answers, a single list as there is a single question
+    fn answer(&self, question: String, context: String) -> Result<Vec<Vec<Answer>>>;
+}
+
+impl QuestionAnswerer for QuestionAnsweringModel {
+    fn answer(&self, question: String, context: String) -> Result<Vec<Vec<Answer>>> {
+        Ok(self.predict(&[QaInput { question, context }], 1, 32))
+    }
+}
+
+impl Pipeline for Box<dyn QuestionAnswerer> {
+    type Config = ClassifierBackend;
+    type Input = (String, String);
+    type Output = Vec<Vec<Answer>>;
+
+    fn load(backend: Self::Config) -> Result<Self> {
+        Ok(match backend {
+            ClassifierBackend::Bert => Box::new(QuestionAnsweringModel::new(
+                QuestionAnsweringConfig::default(),
+            )?),
+            ClassifierBackend::Fake => Box::new(OverlapQuestionAnswering),
+        })
+    }
+
+    fn run(&self, (question, context): Self::Input) -> Result<Self::Output> {
+        self.answer(question, context)
+    }
+}
 
-/// Runner for sentiment classification
+/// Runner for question answering
 #[derive(Debug, Clone)]
 pub struct QuestionAnsweringClassifier {
-    sender: mpsc::SyncSender<Message>,
+    worker: ModelWorker<Box<dyn QuestionAnswerer>>,
 }
 
 impl QuestionAnsweringClassifier {
     /// Spawn a classifier on a separate thread and return a classifier instance
     /// to interact with it
-    pub fn spawn() -> (JoinHandle<Result<()>>, QuestionAnsweringClassifier) {
-        let (sender, receiver) = mpsc::sync_channel(100);
-        let handle = task::spawn_blocking(move || Self::runner(receiver));
-        (handle, QuestionAnsweringClassifier { sender })
-    }
-
-    /// The classification runner itself
-    fn runner(receiver: mpsc::Receiver<Message>) -> Result<()> {
-        // Needs to be in sync runtime, async doesn't work
-        let model = QuestionAnsweringModel::new(QuestionAnsweringConfig::default())?;
-
-        while let Ok((question, context, sender)) = receiver.recv() {
-            let answers = model.predict(&[QaInput { question, context }], 1, 32);
-            sender.send(answers).expect("sending results");
-        }
-
-        Ok(())
+    pub fn spawn(
+        backend: ClassifierBackend,
+    ) -> (JoinHandle<Result<()>>, QuestionAnsweringClassifier) {
+        let (handle, worker) = ModelWorker::spawn(backend);
+        (handle, QuestionAnsweringClassifier { worker })
     }
 
     /// Make the runner predict a sample and return the result
     pub async fn predict(&self, question: String, context: String) -> Result<Vec<Vec<Answer>>> {
-        let (sender, receiver) = oneshot::channel();
-        self.sender.send((question, context, sender))?;
-        Ok(receiver.await?)
+        self.worker.request((question, context)).await
+    }
+
+    /// Answer the question from every chunk of a context and return the best
+    /// scored answer, `None` without chunks
+    pub async fn predict_chunks(
+        &self,
+        question: String,
+        chunks: Vec<String>,
+    ) -> Result<Option<Answer>> {
+        let mut best: Option<Answer> = None;
+        for chunk in chunks {
+            let answers = self.predict(question.clone(), chunk).await?;
+            for answer in answers.into_iter().flatten() {
+                if best.as_ref().is_none_or(|x| answer.score > x.score) {
+                    best = Some(answer);
+                }
+            }
+        }
+        Ok(best)
     }
 }
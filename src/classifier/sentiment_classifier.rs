@@ -1,46 +1,55 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::fake::LexiconSentiment;
+use crate::classifier::model_worker::{ModelWorker, Pipeline};
 use anyhow::Result;
 use rust_bert::pipelines::sentiment::{Sentiment, SentimentConfig, SentimentModel};
-use std::sync::mpsc;
-use tokio::sync::oneshot;
-use tokio::task::{self, JoinHandle};
+use tokio::task::JoinHandle;
 
-/// Message type for internal channel, passing around texts and return value
-/// senders
-type Message = (Vec<String>, oneshot::Sender<Vec<Sentiment>>);
+/// Model classifying english texts as positive or negative
+pub trait SentimentPredictor {
+    fn predict(&self, texts: &[String]) -> Result<Vec<Sentiment>>;
+}
+
+impl SentimentPredictor for SentimentModel {
+    fn predict(&self, texts: &[String]) -> Result<Vec<Sentiment>> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        Ok(SentimentModel::predict(self, texts))
+    }
+}
+
+impl Pipeline for Box<dyn SentimentPredictor> {
+    type Config = ClassifierBackend;
+    type Input = Vec<String>;
+    type Output = Vec<Sentiment>;
+
+    fn load(backend: Self::Config) -> Result<Self> {
+        Ok(match backend {
+            ClassifierBackend::Bert => Box::new(SentimentModel::new(SentimentConfig::default())?),
+            ClassifierBackend::Fake => Box::new(LexiconSentiment),
+        })
+    }
+
+    fn run(&self, texts: Self::Input) -> Result<Self::Output> {
+        self.predict(&texts)
+    }
+}
 
 /// Runner for sentiment classification
 #[derive(Debug, Clone)]
 pub struct SentimentClassifier {
-    sender: mpsc::SyncSender<Message>,
+    worker: ModelWorker<Box<dyn SentimentPredictor>>,
 }
 
 impl SentimentClassifier {
     /// Spawn a classifier on a separate thread and return a classifier instance
     /// to interact with it
-    pub fn spawn() -> (JoinHandle<Result<()>>, SentimentClassifier) {
-        let (sender, receiver) = mpsc::sync_channel(100);
-        let handle = task::spawn_blocking(move || Self::runner(receiver));
-        (handle, SentimentClassifier { sender })
-    }
-
-    /// The classification runner itself
-    fn runner(receiver: mpsc::Receiver<Message>) -> Result<()> {
-        // Needs to be in sync runtime, async doesn't work
-        let model = SentimentModel::new(SentimentConfig::default())?;
-
-        while let Ok((texts, sender)) = receiver.recv() {
-            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
-            let sentiments = model.predict(texts);
-            sender.send(sentiments).expect("sending results");
-        }
-
-        Ok(())
+    pub fn spawn(backend: ClassifierBackend) -> (JoinHandle<Result<()>>, SentimentClassifier) {
+        let (handle, worker) = ModelWorker::spawn(backend);
+        (handle, SentimentClassifier { worker })
     }
 
     /// Make the runner predict a sample and return the result
     pub async fn predict(&self, texts: Vec<String>) -> Result<Vec<Sentiment>> {
-        let (sender, receiver) = oneshot::channel();
-        self.sender.send((texts, sender))?;
-        Ok(receiver.await?)
+        self.worker.request(texts).await
     }
 }
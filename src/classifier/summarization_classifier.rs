@@ -1,46 +1,87 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::chunking;
+use crate::classifier::fake::LeadSummarizer;
+use crate::classifier::model_worker::{ModelWorker, Pipeline};
 use anyhow::Result;
 use rust_bert::pipelines::summarization::{SummarizationConfig, SummarizationModel};
-use std::sync::mpsc;
-use tokio::sync::oneshot;
-use tokio::task::{self, JoinHandle};
+use tokio::task::JoinHandle;
 
-/// Message type for internal channel, passing around texts and return value
-/// senders
-type Message = (Vec<String>, oneshot::Sender<Vec<String>>);
+/// Model summarizing english texts, one summary per text
+pub trait Summarizer {
+    fn summarize(&self, texts: &[String]) -> Result<Vec<String>>;
+}
+
+impl Summarizer for SummarizationModel {
+    fn summarize(&self, texts: &[String]) -> Result<Vec<String>> {
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        Ok(SummarizationModel::summarize(self, &texts)?)
+    }
+}
+
+impl Pipeline for Box<dyn Summarizer> {
+    type Config = ClassifierBackend;
+    type Input = Vec<String>;
+    type Output = Vec<String>;
+
+    fn load(backend: Self::Config) -> Result<Self> {
+        Ok(match backend {
+            ClassifierBackend::Bert => {
+                Box::new(SummarizationModel::new(SummarizationConfig::default())?)
+            }
+            ClassifierBackend::Fake => Box::new(LeadSummarizer),
+        })
+    }
+
+    fn run(&self, texts: Self::Input) -> Result<Self::Output> {
+        self.summarize(&texts)
+    }
+}
 
-/// Runner for sentiment classification
+/// Runner for summarization
 #[derive(Debug, Clone)]
 pub struct SummarizationClassifier {
-    sender: mpsc::SyncSender<Message>,
+    worker: ModelWorker<Box<dyn Summarizer>>,
 }
 
 impl SummarizationClassifier {
     /// Spawn a classifier on a separate thread and return a classifier instance
     /// to interact with it
-    pub fn spawn() -> (JoinHandle<Result<()>>, SummarizationClassifier) {
-        let (sender, receiver) = mpsc::sync_channel(100);
-        let handle = task::spawn_blocking(move || Self::runner(receiver));
-        (handle, SummarizationClassifier { sender })
+    pub fn spawn(backend: ClassifierBackend) -> (JoinHandle<Result<()>>, SummarizationClassifier) {
+        let (handle, worker) = ModelWorker::spawn(backend);
+        (handle, SummarizationClassifier { worker })
     }
 
-    /// The classification runner itself
-    fn runner(receiver: mpsc::Receiver<Message>) -> Result<()> {
-        // Needs to be in sync runtime, async doesn't work
-        let model = SummarizationModel::new(SummarizationConfig::default())?;
+    /// Make the runner predict a sample and return the result
+    pub async fn summarize(&self, texts: Vec<String>) -> Result<Vec<String>> {
+        self.worker.request(texts).await
+    }
 
-        while let Ok((texts, sender)) = receiver.recv() {
-            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
-            let summarize = model.summarize(&texts)?;
-            sender.send(summarize).expect("sending results");
+    /// Summarize a text split into chunks: summarize every chunk, then chunks
+    /// of the summaries until a single summary is left. Empty without chunks
+    pub async fn summarize_chunks(
+        &self,
+        chunks: Vec<String>,
+        max_tokens: usize,
+    ) -> Result<Vec<String>> {
+        if chunks.is_empty() {
+            return Ok(Vec::new());
         }
 
-        Ok(())
-    }
-
-    /// Make the runner predict a sample and return the result
-    pub async fn summarize(&self, texts: Vec<String>) -> Result<Vec<String>> {
-        let (sender, receiver) = oneshot::channel();
-        self.sender.send((texts, sender))?;
-        Ok(receiver.await?)
+        let mut summaries = self.summarize(chunks).await?;
+        while summaries.len() > 1 {
+            let texts: Vec<&str> = summaries.iter().map(String::as_str).collect();
+            let groups = chunking::chunks(&texts, max_tokens);
+            // 요약이 더 묶이지 않으면 모델이 자르더라도 한 번에 요약한다.
+            let texts = if groups.len() == summaries.len() {
+                vec![summaries.join(" ")]
+            } else {
+                groups
+                    .into_iter()
+                    .map(|group| summaries[group].join(" "))
+                    .collect()
+            };
+            summaries = self.summarize(texts).await?;
+        }
+        Ok(summaries)
     }
 }
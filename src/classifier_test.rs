@@ -1,11 +1,21 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::chunking;
+use crate::classifier::classifier_hub::ClassifierHub;
+use crate::classifier::entity_extraction_classifier::EntityExtractionClassifier;
 use crate::classifier::keyword_extraction_classifier::KeywordExtractionClassifier;
+use crate::classifier::model_worker::{ModelWorker, Pipeline};
 use crate::classifier::question_answering_classifier::QuestionAnsweringClassifier;
 use crate::classifier::sentiment_classifier::SentimentClassifier;
 use crate::classifier::summarization_classifier::SummarizationClassifier;
+use crate::error::Error;
+use anyhow::anyhow;
+use rust_bert::pipelines::sentiment::SentimentPolarity;
+use std::time::Duration;
 
 #[tokio::test]
 async fn question_answering_classifier_test() {
-    let (_, question_answering_classifier) = QuestionAnsweringClassifier::spawn();
+    let (_, question_answering_classifier) =
+        QuestionAnsweringClassifier::spawn(ClassifierBackend::Bert);
     let question = String::from("Where does Amy live?");
     let context = String::from("Amy lives in Amsterdam");
 
@@ -18,7 +28,7 @@ async fn question_answering_classifier_test() {
 
 #[tokio::test]
 async fn summarization_classifier_test() {
-    let (_, summarization_classifier) = SummarizationClassifier::spawn();
+    let (_, summarization_classifier) = SummarizationClassifier::spawn(ClassifierBackend::Bert);
     let texts = vec![
         "In findings published Tuesday in Cornell University's arXiv by a team of scientists \
 from the University of Montreal and a separate report published Wednesday in Nature Astronomy by a team \
@@ -49,7 +59,7 @@ about exoplanets like K2-18b.".to_owned(),
 
 #[tokio::test]
 async fn sentiment_classifier_test() {
-    let (_, sentiment_classifier) = SentimentClassifier::spawn();
+    let (_, sentiment_classifier) = SentimentClassifier::spawn(ClassifierBackend::Bert);
     let texts = vec![
         "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.".to_owned(),
         "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...".to_owned(),
@@ -62,7 +72,8 @@ async fn sentiment_classifier_test() {
 
 #[tokio::test]
 async fn keyword_extraction_classifier_test() {
-    let (_, keyword_extraction_classifier) = KeywordExtractionClassifier::spawn();
+    let (_, keyword_extraction_classifier) =
+        KeywordExtractionClassifier::spawn(ClassifierBackend::Bert);
     let texts = vec![
         "Rust is a multi-paradigm, general-purpose programming language. \
        Rust emphasizes performance, type safety, and concurrency. Rust enforces memory safety—that is, \
@@ -76,3 +87,208 @@ async fn keyword_extraction_classifier_test() {
     let keywords = keyword_extraction_classifier.predict(texts).await.unwrap();
     println!("keywords: {keywords:?}");
 }
+
+#[tokio::test]
+async fn classifier_hub_shutdown_test() {
+    let hub = ClassifierHub::spawn(ClassifierBackend::Fake);
+    let sentiment_classifier = hub.sentiment();
+    let sentiments = sentiment_classifier
+        .predict(vec!["배터리가 좋아요".to_owned()])
+        .await
+        .unwrap();
+    assert_eq!(sentiments.len(), 1);
+    drop(sentiment_classifier);
+
+    // 나눠 준 핸들을 모두 닫았으면 작업자가 끝나기를 기다리지 않고 돌아온다.
+    tokio::time::timeout(Duration::from_secs(5), hub.shutdown())
+        .await
+        .expect("runners never exited")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn fake_sentiment_classifier_test() {
+    let (_, sentiment_classifier) = SentimentClassifier::spawn(ClassifierBackend::Fake);
+    let texts = vec![
+        "The battery is great and I love the camera".to_owned(),
+        "It gets hot and the price is bad".to_owned(),
+        "배터리가 정말 좋아요".to_owned(),
+        String::new(),
+        // 사전의 단어로 시작하는 다른 단어는 세지 않는다.
+        "Galaxy Note is great".to_owned(),
+        "Nothing beats the hotel hotspot, notifications are likely fine".to_owned(),
+        "I loved it, the screen is hotter and disappointing".to_owned(),
+    ];
+
+    let sentiments = sentiment_classifier.predict(texts).await.unwrap();
+    let positive: Vec<bool> = sentiments
+        .iter()
+        .map(|x| matches!(x.polarity, SentimentPolarity::Positive))
+        .collect();
+    assert_eq!(positive, [true, false, true, true, true, true, false]);
+    assert_eq!(sentiments[5].score, 0.5);
+    assert!(sentiments[0].score > sentiments[2].score);
+    assert_eq!(sentiments[3].score, 0.5);
+}
+
+#[tokio::test]
+async fn fake_summarization_classifier_test() {
+    let (_, summarization_classifier) = SummarizationClassifier::spawn(ClassifierBackend::Fake);
+    let texts = vec![
+        "The battery lasts all day. The camera is fine.".to_owned(),
+        String::new(),
+    ];
+
+    let summarize = summarization_classifier.summarize(texts).await.unwrap();
+    assert_eq!(summarize, ["The battery lasts all day.", ""]);
+}
+
+#[tokio::test]
+async fn fake_question_answering_classifier_test() {
+    let (_, question_answering_classifier) =
+        QuestionAnsweringClassifier::spawn(ClassifierBackend::Fake);
+    let question = String::from("Where does Amy live?");
+    let context = String::from("Bob works in Paris. Amy lives in Amsterdam. Amy likes tea.");
+
+    let answers = question_answering_classifier
+        .predict(question, context.clone())
+        .await
+        .unwrap();
+    let answer = &answers[0][0];
+    assert_eq!(answer.answer, "Amy lives in Amsterdam.");
+    assert_eq!(&context[answer.start..answer.end], answer.answer);
+}
+
+#[tokio::test]
+async fn fake_keyword_extraction_classifier_test() {
+    let (_, keyword_extraction_classifier) =
+        KeywordExtractionClassifier::spawn(ClassifierBackend::Fake);
+    let texts = vec![
+        "Rust is fast. Rust is safe. The borrow checker keeps Rust safe.".to_owned(),
+        "Python is slow but Python is easy.".to_owned(),
+    ];
+
+    let keywords = keyword_extraction_classifier.predict(texts).await.unwrap();
+    assert_eq!(keywords.len(), 2);
+    assert_eq!(keywords[0][0].text, "rust");
+    assert_eq!(keywords[1][0].text, "python");
+    assert!(keywords.iter().all(|x| x.len() <= 5));
+}
+
+#[tokio::test]
+async fn fake_entity_extraction_classifier_test() {
+    let (_, entity_extraction_classifier) =
+        EntityExtractionClassifier::spawn(ClassifierBackend::Fake);
+    let texts = vec!["The Galaxy S23 beats the iPhone 15 and Pixel Buds Pro. Great.".to_owned()];
+
+    let entities = entity_extraction_classifier.predict(texts).await.unwrap();
+    let words: Vec<&str> = entities[0].iter().map(|x| x.word.as_str()).collect();
+    assert_eq!(words, ["Galaxy S23", "15", "Pixel Buds Pro"]);
+}
+
+#[test]
+fn chunking_test() {
+    assert_eq!(chunking::estimate_tokens("The battery"), 1 + 2);
+    assert_eq!(chunking::estimate_tokens("갤럭시 S23을"), 3 + 2);
+    assert_eq!(chunking::estimate_tokens("  "), 0);
+
+    // 조각을 나누지 않고 예산 안에서 이어 붙인다.
+    let texts = [
+        "one two",
+        "three four",
+        "five",
+        "a very long segment of text",
+    ];
+    let chunks = chunking::chunks(&texts, 5);
+    assert_eq!(chunks, [0..2, 2..3, 3..4]);
+    assert!(chunking::chunks(&[], 4).is_empty());
+}
+
+#[tokio::test]
+async fn fake_summarize_chunks_test() {
+    let (_, summarization_classifier) = SummarizationClassifier::spawn(ClassifierBackend::Fake);
+    let chunks = vec![
+        "The battery lasts all day. The camera is fine.".to_owned(),
+        "It gets hot. The price is high.".to_owned(),
+        "The screen is bright. It is heavy.".to_owned(),
+    ];
+
+    // 조각 요약을 다시 요약해 하나만 남긴다.
+    let summarize = summarization_classifier
+        .summarize_chunks(chunks.clone(), 100)
+        .await
+        .unwrap();
+    assert_eq!(summarize, ["The battery lasts all day."]);
+    // 요약이 예산보다 길어도 끝난다.
+    let summarize = summarization_classifier
+        .summarize_chunks(chunks, 1)
+        .await
+        .unwrap();
+    assert_eq!(summarize.len(), 1);
+    assert!(summarization_classifier
+        .summarize_chunks(Vec::new(), 100)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn fake_question_answering_chunks_test() {
+    let (_, question_answering_classifier) =
+        QuestionAnsweringClassifier::spawn(ClassifierBackend::Fake);
+    let question = String::from("Where does Amy live?");
+    let chunks = vec![
+        "Bob works in Paris.".to_owned(),
+        "Amy lives in Amsterdam.".to_owned(),
+        "Amy likes tea.".to_owned(),
+    ];
+
+    // 뒤쪽 조각의 답이라도 점수가 가장 높으면 고른다.
+    let answer = question_answering_classifier
+        .predict_chunks(question.clone(), chunks)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(answer.answer, "Amy lives in Amsterdam.");
+    assert!(question_answering_classifier
+        .predict_chunks(question, Vec::new())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Model doubling numbers, failing to load when asked to.
+struct Doubler;
+
+impl Pipeline for Doubler {
+    /// whether loading fails
+    type Config = bool;
+    type Input = u32;
+    type Output = u32;
+
+    fn load(fails: bool) -> anyhow::Result<Self> {
+        match fails {
+            true => Err(anyhow!("no weights")),
+            false => Ok(Doubler),
+        }
+    }
+
+    fn run(&self, input: u32) -> anyhow::Result<u32> {
+        Ok(input * 2)
+    }
+}
+
+#[tokio::test]
+async fn model_worker_loaded_test() {
+    let (_, worker) = ModelWorker::<Doubler>::spawn_loaded(false).await.unwrap();
+    assert_eq!(worker.request(21).await.unwrap(), 42);
+
+    // 불러오지 못한 모델의 오류를 그대로 돌려준다.
+    let error = ModelWorker::<Doubler>::spawn_loaded(true)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<Error>(),
+        Some(Error::ModelLoadFailed(message)) if message == "no weights"
+    ));
+}
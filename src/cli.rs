@@ -0,0 +1,167 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::job::queue::KEEP_FINISHED_JOBS;
+use crate::translate::config::Backend;
+use crate::youtube_api::client::CONCURRENCY;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// 최대 비디오 수
+const MAX_RESULTS_VIDEO: usize = 10;
+// 최대 댓글 수
+const MAX_RESULTS_COMMENT: usize = 100;
+
+/// 유튜브를 활용한 제품 추천 시스템
+#[derive(Debug, Parser)]
+#[command(name = "youtube_recommend", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// 검색할 최대 비디오 수
+    #[arg(long, global = true, default_value_t = MAX_RESULTS_VIDEO)]
+    pub videos: usize,
+
+    /// 비디오마다 가져올 최대 댓글 수
+    #[arg(long, global = true, default_value_t = MAX_RESULTS_COMMENT)]
+    pub comments: usize,
+
+    /// 자막 언어 우선순위, 쉼표로 구분한다. Ex: ko,en
+    #[arg(long, global = true, value_delimiter = ',', default_value = "ko")]
+    pub languages: Vec<String>,
+
+    /// 출력 형식
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// 번역기, 주어지지 않으면 TRANSLATOR 환경 변수를 따른다.
+    /// deepl-free, deepl-pro, libre, marian, identity
+    #[arg(long, global = true)]
+    pub translator: Option<Backend>,
+
+    /// 분류 모델, 주어지지 않으면 CLASSIFIER 환경 변수를 따른다.
+    /// bert, fake (내려받지 않는 테스트용 모델)
+    #[arg(long, global = true)]
+    pub classifier: Option<ClassifierBackend>,
+
+    /// 댓글 감성을 나눌 속성 사전 json 파일, 주어지지 않으면 ASPECT_LEXICON 환경 변수를 따른다.
+    /// 둘 다 없으면 내장 사전을 쓴다. Ex: [{"name": "battery", "terms": ["battery", "배터리"]}]
+    #[arg(long, global = true)]
+    pub aspects: Option<PathBuf>,
+
+    /// 분석 결과를 쓸 파일, 주어지지 않으면 표준 출력에 쓴다
+    #[arg(long, global = true)]
+    pub output: Option<PathBuf>,
+
+    /// 캐시를 쓰지 않고 매번 새로 가져온다. 캐시 위치는 CACHE_DIR 환경 변수, 기본값은 .cache
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// 실행 기록을 저장할 sqlite 파일, 주어지지 않으면 DATABASE_PATH 환경 변수, 기본값은 youtube_recommend.db
+    #[arg(long, global = true)]
+    pub db: Option<PathBuf>,
+
+    /// 실행 기록을 저장하지 않는다
+    #[arg(long, global = true)]
+    pub no_store: bool,
+
+    /// 자막과 댓글을 동시에 가져올 비디오 수
+    #[arg(long, global = true, default_value_t = CONCURRENCY)]
+    pub concurrency: usize,
+
+    /// YouTube Data API 키, 주어지지 않으면 YOUTUBE_API_KEY 환경 변수를 따른다
+    #[arg(long, global = true)]
+    pub youtube_api_key: Option<String>,
+
+    /// YouTube Data API 주소, 주어지지 않으면 YOUTUBE_API_URL 환경 변수를 따른다
+    #[arg(long, global = true)]
+    pub youtube_api_url: Option<String>,
+
+    /// 시청 페이지와 자막을 가져올 주소, 주어지지 않으면 YOUTUBE_URL 환경 변수를 따른다
+    #[arg(long, global = true)]
+    pub youtube_url: Option<String>,
+
+    /// 호스트로 보내는 요청 사이의 최소 간격(밀리초), 여러 번 줄 수 있다. Ex: www.youtube.com=500
+    #[arg(long, global = true, value_parser = parse_rate_limit)]
+    pub rate_limit: Vec<(String, Duration)>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 검색어로 비디오를 검색한다
+    Search { query: String },
+    /// 비디오의 자막을 가져온다
+    Transcript { video_id: String },
+    /// 비디오의 댓글을 가져온다
+    Comments { video_id: String },
+    /// 비디오 하나를 분석한다
+    Analyze { video_id: String },
+    /// 검색어로 찾은 비디오들을 분석해 제품을 추천한다
+    Recommend { query: String },
+    /// 저장된 실행 기록을 보여준다
+    History {
+        /// 실행마다 이 제품의 순위와 점수를 보여준다
+        #[arg(long)]
+        product: Option<String>,
+        /// 보여줄 최대 실행 수
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// 저장된 실행의 자막과 댓글로 모델만 다시 실행한다
+    Rerun { run_id: i64 },
+    /// 분석 파이프라인을 http api로 제공한다
+    Serve {
+        /// 서버 주소
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: SocketAddr,
+        /// 동시에 실행할 분석 작업 수, 나머지는 대기한다
+        #[arg(long, default_value_t = 1)]
+        jobs: usize,
+        /// 결과를 남겨 둘 끝난 작업 수, 오래된 작업부터 지운다
+        #[arg(long, default_value_t = KEEP_FINISHED_JOBS)]
+        keep_jobs: usize,
+    },
+    /// 녹화된 외부 api 응답을 돌려주는 서버를 띄운다. --record 면 실제 api 응답을 녹화한다
+    Fixtures {
+        /// 서버 주소
+        #[arg(long, default_value = "127.0.0.1:8090")]
+        addr: SocketAddr,
+        /// 픽스처 디렉터리
+        #[arg(long, default_value = "fixtures")]
+        dir: PathBuf,
+        /// 요청을 실제 api로 보내고 응답을 녹화한다
+        #[arg(long)]
+        record: bool,
+        /// 녹화할 api 주소를 바꾼다. Ex: libre=http://localhost:5000
+        #[arg(long, value_parser = parse_upstream)]
+        upstream: Vec<(String, String)>,
+    },
+}
+
+fn parse_upstream(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, url)| (name.to_owned(), url.to_owned()))
+        .ok_or_else(|| format!("expected NAME=URL, got {}", s))
+}
+
+fn parse_rate_limit(s: &str) -> Result<(String, Duration), String> {
+    s.split_once('=')
+        .and_then(|(host, millis)| Some((host.to_owned(), millis.parse().ok()?)))
+        .map(|(host, millis)| (host, Duration::from_millis(millis)))
+        .ok_or_else(|| format!("expected HOST=MILLISECONDS, got {}", s))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// 사람이 읽기 위한 출력
+    Text,
+    /// json
+    Json,
+    /// 비디오마다 한 줄씩 쓰는 json lines
+    Jsonl,
+    /// 그대로 열어볼 수 있는 html 리포트
+    Html,
+    /// markdown 리포트
+    Markdown,
+}
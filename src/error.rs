@@ -0,0 +1,120 @@
+use crate::youtube_transcript;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::{error::Error as StdError, fmt::Display};
+
+/// Errors raised while running the recommendation pipeline.
+#[derive(Debug)]
+pub enum Error {
+    /// non-success response that is not covered by the other variants
+    Http {
+        status: StatusCode,
+        body: String,
+    },
+    /// the YouTube Data API daily quota or rate limit is used up
+    QuotaExceeded(String),
+    /// the owner of the video turned comments off
+    CommentsDisabled(String),
+    /// the transcript of a video could not be fetched or parsed
+    TranscriptUnavailable(youtube_transcript::error::Error),
+    /// the translation API rejected the request or returned no translation
+    TranslationFailed(String),
+    /// a rust-bert model could not be loaded
+    ModelLoadFailed(String),
+    /// the request could not be sent or its body could not be decoded
+    Request(reqwest::Error),
+    /// a response body, fresh or cached, is not the expected json
+    Json(serde_json::Error),
+    Io(std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Http { status, body } => write!(f, "http error {}: {}", status, body),
+            Error::QuotaExceeded(message) => write!(f, "quota exceeded: {}", message),
+            Error::CommentsDisabled(message) => write!(f, "comments disabled: {}", message),
+            Error::TranscriptUnavailable(error) => write!(f, "transcript unavailable: {}", error),
+            Error::TranslationFailed(message) => write!(f, "translation failed: {}", message),
+            Error::ModelLoadFailed(message) => write!(f, "model load failed: {}", message),
+            Error::Request(error) => write!(f, "request failed: {}", error),
+            Error::Json(error) => write!(f, "invalid json: {}", error),
+            Error::Io(error) => write!(f, "io error: {}", error),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::TranscriptUnavailable(error) => Some(error),
+            Error::Request(error) => Some(error),
+            Error::Json(error) => Some(error),
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        Error::Request(value)
+    }
+}
+
+impl From<youtube_transcript::error::Error> for Error {
+    fn from(value: youtube_transcript::error::Error) -> Self {
+        Error::TranscriptUnavailable(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Error::Json(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+#[derive(Deserialize)]
+struct GoogleErrorBody {
+    error: GoogleError,
+}
+
+#[derive(Deserialize)]
+struct GoogleError {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    errors: Vec<GoogleErrorReason>,
+}
+
+#[derive(Deserialize)]
+struct GoogleErrorReason {
+    #[serde(default)]
+    reason: String,
+}
+
+impl Error {
+    /// classifies a failed YouTube Data API response by the `reason` of its error body.
+    pub(crate) fn from_youtube_response(status: StatusCode, body: String) -> Self {
+        let Ok(GoogleErrorBody { error }) = serde_json::from_str::<GoogleErrorBody>(&body) else {
+            return Error::Http { status, body };
+        };
+        let reason = error.errors.first().map(|x| x.reason.as_str());
+
+        match reason {
+            Some("quotaExceeded" | "dailyLimitExceeded" | "rateLimitExceeded") => {
+                Error::QuotaExceeded(error.message)
+            }
+            Some("commentsDisabled") => Error::CommentsDisabled(error.message),
+            _ => Error::Http { status, body },
+        }
+    }
+}
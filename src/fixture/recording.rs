@@ -0,0 +1,161 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+// 픽스처에 남기지 않는 인증 파라미터
+const SECRET_PARAMS: [&str; 3] = ["key", "api_key", "auth_key"];
+// 파일 이름에 쓰는 경로 길이
+const MAX_SLUG: usize = 60;
+
+/// Request a recording answers, without its credentials.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// upstream name followed by the path. Ex: "youtube-api/search"
+    pub path: String,
+    /// query parameters sorted by name
+    #[serde(default)]
+    pub query: Vec<(String, String)>,
+    /// json bodies are kept as json, other bodies as a string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+impl RecordedRequest {
+    /// `query` is the raw query string, Ex: "part=snippet&q=S23".
+    pub fn new(method: &str, path: &str, query: Option<&str>, body: &[u8]) -> Self {
+        let mut url = Url::parse("http://fixture/").expect("valid url");
+        url.set_query(query);
+        let mut query: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(name, _)| !SECRET_PARAMS.contains(&name.as_ref()))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        query.sort();
+
+        let body = (!body.is_empty()).then(|| match serde_json::from_slice(body) {
+            Ok(Value::Object(mut object)) => {
+                for name in SECRET_PARAMS {
+                    object.remove(name);
+                }
+                Value::Object(object)
+            }
+            Ok(value) => value,
+            Err(_) => Value::String(String::from_utf8_lossy(body).into_owned()),
+        });
+
+        Self {
+            method: method.to_uppercase(),
+            path: path.trim_matches('/').to_owned(),
+            query,
+            body,
+        }
+    }
+
+    /// upstream the request is sent to, the first segment of the path.
+    pub fn upstream(&self) -> &str {
+        self.path.split('/').next().unwrap_or_default()
+    }
+
+    /// readable part of the path and a hash of the whole request.
+    /// Ex: "search-3f2a9c01d4e7.json"
+    pub fn file_name(&self) -> String {
+        let json = serde_json::to_string(self).expect("serializable request");
+        let hash = format!("{:x}", Sha256::digest(json.as_bytes()));
+        let path = self.path.split_once('/').map_or("", |(_, path)| path);
+        let mut slug: String = path
+            .chars()
+            .map(|x| if x.is_ascii_alphanumeric() { x } else { '_' })
+            .take(MAX_SLUG)
+            .collect();
+        if slug.is_empty() {
+            slug.push_str("index");
+        }
+        format!("{}-{}.json", slug, &hash[..12])
+    }
+}
+
+/// Body of a recorded response, json bodies being kept as json so that the
+/// files stay readable and editable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecordedBody {
+    Text(String),
+    Json(Value),
+}
+
+impl RecordedBody {
+    pub fn new(content_type: Option<&str>, body: String) -> Self {
+        if content_type.is_some_and(|x| x.contains("json")) {
+            if let Ok(value) = serde_json::from_str(&body) {
+                return RecordedBody::Json(value);
+            }
+        }
+        RecordedBody::Text(body)
+    }
+
+    pub fn to_text(&self) -> String {
+        match self {
+            RecordedBody::Text(text) => text.clone(),
+            RecordedBody::Json(value) => value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub body: RecordedBody,
+}
+
+/// Request and the response it got, as written in a fixture file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// Directory of recordings, one json file per request under a directory per
+/// upstream. Ex: "fixtures/youtube-api/search-3f2a9c01d4e7.json"
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    dir: PathBuf,
+}
+
+impl Fixtures {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, request: &RecordedRequest) -> PathBuf {
+        self.dir.join(request.upstream()).join(request.file_name())
+    }
+
+    /// response recorded for `request`, `None` if it was never recorded.
+    pub fn load(&self, request: &RecordedRequest) -> anyhow::Result<Option<RecordedResponse>> {
+        let path = self.path(request);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let recording: Recording = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(Some(recording.response))
+    }
+
+    /// writes the recording, replacing the previous one of the same request.
+    pub fn save(&self, recording: &Recording) -> anyhow::Result<PathBuf> {
+        let path = self.path(&recording.request);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(recording)? + "\n")?;
+        Ok(path)
+    }
+}
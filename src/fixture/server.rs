@@ -0,0 +1,211 @@
+use crate::fixture::recording::{
+    Fixtures, RecordedBody, RecordedRequest, RecordedResponse, Recording,
+};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE};
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use reqwest::Client;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// upstreams recorded by default, by the first segment of the path.
+pub const UPSTREAMS: [(&str, &str); 5] = [
+    ("youtube-api", "https://www.googleapis.com/youtube/v3"),
+    ("youtube", "https://www.youtube.com"),
+    ("deepl-free", "https://api-free.deepl.com"),
+    ("deepl-pro", "https://api.deepl.com"),
+    ("libre", "http://localhost:5000"),
+];
+
+/// Whether the server answers from the fixtures or records new ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// answers only from the fixtures, never reaching the network
+    Replay,
+    /// forwards every request to its upstream and saves the response
+    Record,
+}
+
+/// Local server standing in for the YouTube and translation apis. Clients
+/// are pointed at it with their base urls, Ex: `YOUTUBE_API_URL` set to
+/// "http://127.0.0.1:8090/youtube-api".
+#[derive(Debug, Clone)]
+pub struct FixtureServer {
+    fixtures: Fixtures,
+    mode: Mode,
+    upstreams: BTreeMap<String, String>,
+    client: Client,
+}
+
+impl FixtureServer {
+    pub fn new(fixtures: Fixtures, mode: Mode) -> Self {
+        Self {
+            fixtures,
+            mode,
+            upstreams: UPSTREAMS
+                .iter()
+                .map(|(name, url)| (name.to_string(), url.to_string()))
+                .collect(),
+            client: Client::new(),
+        }
+    }
+
+    pub fn replay(fixtures: Fixtures) -> Self {
+        Self::new(fixtures, Mode::Replay)
+    }
+
+    pub fn record(fixtures: Fixtures) -> Self {
+        Self::new(fixtures, Mode::Record)
+    }
+
+    /// records the requests under `/{name}` from `url`, replacing the
+    /// default upstream of that name.
+    pub fn upstream(mut self, name: impl Into<String>, url: impl Into<String>) -> Self {
+        self.upstreams
+            .insert(name.into(), url.into().trim_end_matches('/').to_owned());
+        self
+    }
+
+    pub fn router(self) -> Router {
+        Router::new().fallback(handle).with_state(Arc::new(self))
+    }
+
+    /// serves in the background and returns the bound address, used by the
+    /// tests with port 0.
+    pub async fn spawn(self, addr: SocketAddr) -> anyhow::Result<SocketAddr> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, self.router()).await });
+        Ok(addr)
+    }
+
+    /// serves until ctrl-c, printing the variables pointing the clients at it.
+    pub async fn serve(self, addr: SocketAddr) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        println!(
+            "{:?} {} on http://{}",
+            self.mode,
+            self.fixtures.dir().display(),
+            addr
+        );
+        for line in env(addr) {
+            println!("{}", line);
+        }
+
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(async {
+                tokio::signal::ctrl_c().await.ok();
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn forward(
+        &self,
+        request: &RecordedRequest,
+        uri: &Uri,
+        method: Method,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) -> anyhow::Result<RecordedResponse> {
+        let path = request.path.split_once('/').map_or("", |(_, path)| path);
+        let upstream = request.upstream();
+        let base_url = self
+            .upstreams
+            .get(upstream)
+            .ok_or_else(|| anyhow::anyhow!("unknown upstream: {}", upstream))?;
+        let mut url = format!("{}/{}", base_url, path);
+        if let Some(query) = uri.query() {
+            url = format!("{}?{}", url, query);
+        }
+
+        let method = reqwest::Method::from_bytes(method.as_str().as_bytes())?;
+        let mut builder = self.client.request(method, url).body(body.to_vec());
+        for name in [AUTHORIZATION, CONTENT_TYPE] {
+            if let Some(value) = headers.get(&name).and_then(|x| x.to_str().ok()) {
+                builder = builder.header(name.as_str(), value);
+            }
+        }
+        let response = builder.send().await?;
+
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|x| x.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await?;
+        Ok(RecordedResponse {
+            status,
+            body: RecordedBody::new(content_type.as_deref(), body),
+            content_type,
+        })
+    }
+}
+
+async fn handle(
+    State(server): State<Arc<FixtureServer>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let request = RecordedRequest::new(method.as_str(), uri.path(), uri.query(), &body);
+
+    let response = match server.mode {
+        Mode::Replay => server.fixtures.load(&request),
+        Mode::Record => match server.forward(&request, &uri, method, &headers, body).await {
+            Ok(response) => {
+                let recording = Recording {
+                    request: request.clone(),
+                    response,
+                };
+                server
+                    .fixtures
+                    .save(&recording)
+                    .map(|_| Some(recording.response))
+            }
+            Err(error) => Err(error),
+        },
+    };
+
+    match response {
+        Ok(Some(response)) => into_response(response),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!(
+                "no fixture for {} {}, expected {}",
+                request.method,
+                request.path,
+                server.fixtures.path(&request).display()
+            ),
+        )
+            .into_response(),
+        Err(error) => (StatusCode::BAD_GATEWAY, error.to_string()).into_response(),
+    }
+}
+
+fn into_response(response: RecordedResponse) -> Response {
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::OK);
+    let mut headers = HeaderMap::new();
+    if let Some(content_type) = response.content_type.and_then(|x| x.parse().ok()) {
+        headers.insert(CONTENT_TYPE, content_type);
+    }
+    (status, headers, response.body.to_text()).into_response()
+}
+
+/// variables pointing the clients at a fixture server listening on `addr`.
+pub fn env(addr: SocketAddr) -> Vec<String> {
+    vec![
+        format!("YOUTUBE_API_URL=http://{}/youtube-api", addr),
+        format!("YOUTUBE_URL=http://{}/youtube", addr),
+        format!("DEEPL_API_URL=http://{}/deepl-free/v2/translate", addr),
+        format!("LIBRETRANSLATE_URL=http://{}/libre", addr),
+    ]
+}
@@ -0,0 +1,264 @@
+use crate::cache::Cache;
+use crate::fixture::recording::{Fixtures, RecordedRequest};
+use crate::fixture::server::FixtureServer;
+use crate::pipeline;
+use crate::rate_limit::RateLimiter;
+use crate::translate::deepl::DeepLTranslator;
+use crate::translate::translator::Translator;
+use crate::youtube_api::client::YoutubeApiClient;
+use crate::youtube_transcript::config::{CaptionSource, Config};
+use crate::youtube_transcript::youtube::YoutubeBuilder;
+use axum::routing::get;
+use axum::{Json, Router};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio::net::TcpListener;
+
+const LOCALHOST: &str = "127.0.0.1:0";
+
+/// fixtures recorded in the repository.
+fn fixtures() -> Fixtures {
+    Fixtures::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures"))
+}
+
+fn temporary_fixtures(name: &str) -> Fixtures {
+    let dir = std::env::temp_dir().join(format!(
+        "youtube_recommend_fixtures_{}_{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    Fixtures::new(dir)
+}
+
+/// copy of the fixtures of the repository, for a test to change.
+pub(crate) fn copy_fixtures(name: &str) -> Fixtures {
+    let copy = temporary_fixtures(name);
+    for upstream in std::fs::read_dir(fixtures().dir()).unwrap() {
+        let upstream = upstream.unwrap().path();
+        let dir = copy.dir().join(upstream.file_name().unwrap());
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in std::fs::read_dir(&upstream).unwrap() {
+            let file = file.unwrap().path();
+            std::fs::copy(&file, dir.join(file.file_name().unwrap())).unwrap();
+        }
+    }
+    copy
+}
+
+/// replays the fixtures of the repository, on a port of its own.
+pub(crate) async fn replay() -> SocketAddr {
+    FixtureServer::replay(fixtures())
+        .spawn(LOCALHOST.parse().unwrap())
+        .await
+        .unwrap()
+}
+
+pub(crate) fn youtube_api(addr: SocketAddr) -> YoutubeApiClient {
+    YoutubeApiClient::new("test-key")
+        .with_base_url(format!("http://{}/youtube-api", addr))
+        .with_web_url(format!("http://{}/youtube", addr))
+}
+
+/// request of the player api for the caption tracks of `video_id`.
+pub(crate) fn player_request(video_id: &str) -> RecordedRequest {
+    let body = serde_json::json!({
+        "context": { "client": { "clientName": "ANDROID", "clientVersion": "20.10.38" } },
+        "videoId": video_id,
+    });
+    RecordedRequest::new(
+        "POST",
+        "youtube/youtubei/v1/player",
+        Some("prettyPrint=false"),
+        body.to_string().as_bytes(),
+    )
+}
+
+#[test]
+fn fixture_request_test() {
+    let request = RecordedRequest::new(
+        "get",
+        "/youtube-api/search",
+        Some("q=S23&key=secret&part=snippet"),
+        b"",
+    );
+    assert_eq!(request.upstream(), "youtube-api");
+    assert_eq!(
+        request.query,
+        [
+            ("part".to_owned(), "snippet".to_owned()),
+            ("q".to_owned(), "S23".to_owned())
+        ]
+    );
+    assert!(request.body.is_none());
+
+    // 인증 정보가 달라도 같은 픽스처를 쓴다.
+    let other_key = RecordedRequest::new(
+        "GET",
+        "youtube-api/search",
+        Some("part=snippet&q=S23&key=other"),
+        b"",
+    );
+    assert_eq!(request.file_name(), other_key.file_name());
+    assert!(request.file_name().starts_with("search-"));
+
+    let body = RecordedRequest::new(
+        "POST",
+        "libre/translate",
+        None,
+        br#"{"q":["a"],"api_key":"secret"}"#,
+    );
+    assert_eq!(body.body, Some(serde_json::json!({ "q": ["a"] })));
+}
+
+#[tokio::test]
+async fn fixture_replay_youtube_api_test() {
+    let youtube_api = youtube_api(replay().await);
+
+    let videos = pipeline::get_videos(&youtube_api, "갤럭시 S23", 2)
+        .await
+        .unwrap();
+    // 검색 결과의 순서를 따른다.
+    assert_eq!(
+        videos.iter().map(|x| x.id.as_str()).collect::<Vec<_>>(),
+        ["Xk3bQ9m2LpA", "Vt7nR1cZ8dE"]
+    );
+    assert_eq!(videos[0].statistics.view_count, Some(184233));
+
+    let comments = pipeline::get_comments(&youtube_api, "Xk3bQ9m2LpA", 3)
+        .await
+        .unwrap();
+    assert_eq!(comments.len(), 3);
+    assert_eq!(comments[0].text(), "배터리가 정말 오래가요");
+
+    // 댓글이 꺼진 비디오는 403 commentsDisabled 로 녹화되어 있다.
+    let comments = pipeline::get_comments(&youtube_api, "Vt7nR1cZ8dE", 3)
+        .await
+        .unwrap();
+    assert!(comments.is_empty());
+
+    // 녹화되지 않은 요청은 네트워크로 나가지 않고 실패한다.
+    assert!(youtube_api.search("녹화되지 않은 검색어", 1).await.is_err());
+}
+
+#[tokio::test]
+async fn fixture_replay_transcript_test() {
+    let youtube_api = youtube_api(replay().await);
+
+    let transcript = pipeline::get_transcript(
+        &Cache::disabled(),
+        youtube_api.rate_limiter(),
+        youtube_api.web_url(),
+        &["ko".to_owned()],
+        "Xk3bQ9m2LpA",
+    )
+    .await
+    .unwrap();
+    // 자동 생성 자막보다 직접 쓴 자막을 고른다.
+    let caption = transcript.caption.as_ref().unwrap();
+    assert_eq!(caption.lang_code, "ko");
+    assert_eq!(caption.kind, None);
+    assert_eq!(transcript.transcripts.len(), 4);
+    assert_eq!(transcript.transcripts[0].text, "안녕하세요 테크리뷰입니다");
+}
+
+#[tokio::test]
+async fn fixture_replay_caption_source_test() {
+    // 두 번째 비디오의 플레이어 응답을 뺀 픽스처
+    let fixtures = copy_fixtures("caption_source");
+    std::fs::remove_file(fixtures.path(&player_request("Vt7nR1cZ8dE"))).unwrap();
+    let addr = FixtureServer::replay(fixtures.clone())
+        .spawn(LOCALHOST.parse().unwrap())
+        .await
+        .unwrap();
+    let caption_tracks = |config: &'static Config, video_id: &'static str| async move {
+        let builder = YoutubeBuilder::default()
+            .config(config)
+            .rate_limiter(RateLimiter::unlimited())
+            .base_url(format!("http://{}/youtube", addr));
+        let youtube = builder.build();
+        youtube
+            .caption_tracks(&youtube.watch_url(video_id))
+            .await
+            .unwrap()
+    };
+    let innertube: &'static Config = Box::leak(Box::default());
+    let html: &'static Config =
+        Box::leak(Box::new(Config::default().with_source(CaptionSource::Html)));
+
+    // 플레이어 api 와 시청 페이지의 자막 목록은 같다.
+    let from_player = caption_tracks(innertube, "Xk3bQ9m2LpA").await;
+    let from_page = caption_tracks(html, "Xk3bQ9m2LpA").await;
+    assert_eq!(from_player.len(), 2);
+    assert_eq!(
+        from_player.iter().map(|x| &x.kind).collect::<Vec<_>>(),
+        from_page.iter().map(|x| &x.kind).collect::<Vec<_>>()
+    );
+
+    // 플레이어 api 가 실패하면 시청 페이지를 읽는다.
+    assert!(caption_tracks(innertube, "Vt7nR1cZ8dE").await.is_empty());
+
+    std::fs::remove_dir_all(fixtures.dir()).unwrap();
+}
+
+#[tokio::test]
+async fn fixture_replay_deepl_test() {
+    let addr = replay().await;
+    let translator = DeepLTranslator::with_url(
+        "test-key",
+        format!("http://{}/deepl-free/v2/translate", addr),
+    );
+
+    let texts = [
+        "배터리가 정말 오래가요".to_owned(),
+        String::new(),
+        "발열이 좀 있네요".to_owned(),
+    ];
+    let translations = translator.translate(&texts, "KO", "EN").await.unwrap();
+    assert_eq!(
+        translations,
+        ["The battery really lasts long", "", "It gets a little hot"]
+    );
+}
+
+#[tokio::test]
+async fn fixture_record_test() {
+    // 실제 api 대신 녹화할 서버
+    let listener = TcpListener::bind(LOCALHOST).await.unwrap();
+    let upstream = listener.local_addr().unwrap();
+    let router = Router::new().route(
+        "/v1/items",
+        get(|| async { Json(serde_json::json!({ "items": [1, 2, 3] })) }),
+    );
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let fixtures = temporary_fixtures("record");
+    let recorder = FixtureServer::record(fixtures.clone())
+        .upstream("fake", format!("http://{}", upstream))
+        .spawn(LOCALHOST.parse().unwrap())
+        .await
+        .unwrap();
+    let url = |addr: SocketAddr| format!("http://{}/fake/v1/items?key=secret&page=1", addr);
+
+    let recorded = reqwest::get(url(recorder)).await.unwrap();
+    assert!(recorded.status().is_success());
+    let recorded = recorded.text().await.unwrap();
+
+    let request = RecordedRequest::new("GET", "fake/v1/items", Some("page=1"), b"");
+    let file = std::fs::read_to_string(fixtures.path(&request)).unwrap();
+    assert!(!file.contains("secret"));
+
+    let replayer = FixtureServer::replay(fixtures.clone())
+        .spawn(LOCALHOST.parse().unwrap())
+        .await
+        .unwrap();
+    let replayed = reqwest::get(url(replayer)).await.unwrap();
+    assert!(replayed.status().is_success());
+    assert_eq!(
+        replayed.headers()["content-type"].to_str().unwrap(),
+        "application/json"
+    );
+    assert_eq!(replayed.text().await.unwrap(), recorded);
+
+    std::fs::remove_dir_all(fixtures.dir()).unwrap();
+}
@@ -0,0 +1,51 @@
+use serde::Serialize;
+
+/// Stages of a run, in the order they happen for each video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Search,
+    Transcript,
+    Comments,
+    Translate,
+    QuestionAnswering,
+    Summarize,
+    Sentiment,
+    Keywords,
+    Products,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 9] = [
+        Stage::Search,
+        Stage::Transcript,
+        Stage::Comments,
+        Stage::Translate,
+        Stage::QuestionAnswering,
+        Stage::Summarize,
+        Stage::Sentiment,
+        Stage::Keywords,
+        Stage::Products,
+    ];
+
+    /// the search happens once per run, the other stages once per video.
+    pub fn per_video(self) -> bool {
+        self != Stage::Search
+    }
+}
+
+/// Receives the progress of a run. Every method does nothing by default.
+pub trait Progress: Send + Sync {
+    /// number of videos the per-video stages go through.
+    fn videos(&self, _count: usize) {}
+
+    /// `stage` started, for `video_id` if it is a per-video stage.
+    fn start(&self, _stage: Stage, _video_id: Option<&str>) {}
+
+    fn finish(&self, _stage: Stage) {}
+}
+
+/// [`Progress`] ignoring everything, for runs nobody watches.
+pub struct NoProgress;
+
+impl Progress for NoProgress {}
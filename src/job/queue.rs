@@ -0,0 +1,316 @@
+use crate::job::progress::{Progress, Stage};
+use crate::report::model::{RunOptions, RunReport, VideoAnalysis};
+use crate::time::unix_time;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
+
+/// Finished jobs kept with their report, older ones are dropped.
+pub const KEEP_FINISHED_JOBS: usize = 100;
+
+/// Status of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// waiting for a running job to finish
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// Status of a stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Pending,
+    Running,
+    Done,
+}
+
+/// Progress of a stage, `done` out of `total` videos for per-video stages.
+#[derive(Debug, Clone, Serialize)]
+pub struct StageProgress {
+    pub stage: Stage,
+    pub status: StageStatus,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Stage currently running.
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrentStage {
+    pub stage: Stage,
+    pub video_id: Option<String>,
+}
+
+/// Snapshot of a job, without its report.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobSummary {
+    pub id: u64,
+    pub query: String,
+    pub options: RunOptions,
+    pub status: JobStatus,
+    /// unix time in seconds
+    pub submitted_at: u64,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    pub current: Option<CurrentStage>,
+    pub stages: Vec<StageProgress>,
+    pub error: Option<String>,
+}
+
+/// Job submitted to a [`JobQueue`]. It is the [`Progress`] of its own run.
+#[derive(Debug)]
+pub struct Job {
+    summary: Mutex<JobSummary>,
+    /// videos analyzed so far, as in the report
+    analyses: Mutex<Vec<Arc<VideoAnalysis>>>,
+    report: Mutex<Option<Arc<RunReport>>>,
+    abort: Mutex<Option<AbortHandle>>,
+}
+
+impl Job {
+    fn new(id: u64, query: &str, options: RunOptions) -> Self {
+        Self {
+            summary: Mutex::new(JobSummary {
+                id,
+                query: query.to_owned(),
+                options,
+                status: JobStatus::Queued,
+                submitted_at: unix_time(),
+                started_at: None,
+                finished_at: None,
+                current: None,
+                stages: Stage::ALL
+                    .iter()
+                    .map(|&stage| StageProgress {
+                        stage,
+                        status: StageStatus::Pending,
+                        done: 0,
+                        total: usize::from(!stage.per_video()),
+                    })
+                    .collect(),
+                error: None,
+            }),
+            analyses: Mutex::new(Vec::new()),
+            report: Mutex::new(None),
+            abort: Mutex::new(None),
+        }
+    }
+
+    pub fn summary(&self) -> JobSummary {
+        self.summary.lock().unwrap().clone()
+    }
+
+    fn status(&self) -> JobStatus {
+        self.summary.lock().unwrap().status
+    }
+
+    /// keeps a video as soon as it is analyzed, before the run is done.
+    pub fn add_analysis(&self, analysis: Arc<VideoAnalysis>) {
+        self.analyses.lock().unwrap().push(analysis);
+    }
+
+    /// videos analyzed so far, in the order of the run.
+    pub fn analyses(&self) -> Vec<Arc<VideoAnalysis>> {
+        self.analyses.lock().unwrap().clone()
+    }
+
+    /// the report of the run, once it is done.
+    pub fn report(&self) -> Option<Arc<RunReport>> {
+        self.report.lock().unwrap().clone()
+    }
+
+    fn set_status(&self, status: JobStatus) {
+        let mut summary = self.summary.lock().unwrap();
+        if summary.status.is_finished() {
+            return;
+        }
+        summary.status = status;
+        match status {
+            JobStatus::Queued => {}
+            JobStatus::Running => summary.started_at = Some(unix_time()),
+            _ => {
+                summary.finished_at = Some(unix_time());
+                summary.current = None;
+            }
+        }
+    }
+
+    fn finish_with(&self, result: anyhow::Result<RunReport>) {
+        match result {
+            Ok(report) => {
+                *self.report.lock().unwrap() = Some(Arc::new(report));
+                self.set_status(JobStatus::Done);
+            }
+            Err(error) => {
+                self.summary.lock().unwrap().error = Some(format!("{:#}", error));
+                self.set_status(JobStatus::Failed);
+            }
+        }
+    }
+
+    fn stage<T>(&self, stage: Stage, f: impl FnOnce(&mut StageProgress) -> T) -> T {
+        let mut summary = self.summary.lock().unwrap();
+        let progress = summary
+            .stages
+            .iter_mut()
+            .find(|x| x.stage == stage)
+            .expect("every stage is tracked");
+        f(progress)
+    }
+}
+
+impl Progress for Job {
+    fn videos(&self, count: usize) {
+        let mut summary = self.summary.lock().unwrap();
+        for progress in summary.stages.iter_mut().filter(|x| x.stage.per_video()) {
+            progress.total = count;
+        }
+    }
+
+    fn start(&self, stage: Stage, video_id: Option<&str>) {
+        self.stage(stage, |x| x.status = StageStatus::Running);
+        self.summary.lock().unwrap().current = Some(CurrentStage {
+            stage,
+            video_id: video_id.map(str::to_string),
+        });
+    }
+
+    fn finish(&self, stage: Stage) {
+        self.stage(stage, |x| {
+            x.done += 1;
+            if x.done >= x.total {
+                x.status = StageStatus::Done;
+            }
+        });
+    }
+}
+
+/// Runs submitted jobs in the background on the tokio runtime, a limited
+/// number at a time since they share the classifier workers. Only the most
+/// recent finished jobs are kept.
+#[derive(Debug)]
+pub struct JobQueue {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, Arc<Job>>>,
+    permits: Arc<Semaphore>,
+    keep_finished: usize,
+}
+
+impl JobQueue {
+    /// creates a queue running up to `concurrency` jobs at once.
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            jobs: Mutex::new(HashMap::new()),
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            keep_finished: KEEP_FINISHED_JOBS,
+        }
+    }
+
+    /// keeps the last `keep` finished jobs, [`KEEP_FINISHED_JOBS`] by default.
+    pub fn with_keep_finished(mut self, keep: usize) -> Self {
+        self.keep_finished = keep;
+        self
+    }
+
+    /// queues `work`, called with the job to report its progress to, and
+    /// returns the id of the job.
+    pub fn submit<F, Fut>(&self, query: &str, options: RunOptions, work: F) -> u64
+    where
+        F: FnOnce(Arc<Job>) -> Fut + Send + 'static,
+        Fut: Future<Output = anyhow::Result<RunReport>> + Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let job = Arc::new(Job::new(id, query, options));
+        {
+            let mut jobs = self.jobs.lock().unwrap();
+            self.evict(&mut jobs);
+            jobs.insert(id, job.clone());
+        }
+
+        let permits = self.permits.clone();
+        // 취소와 같은 잠금 아래에서 띄워야 abort 핸들이 기록되기 전에 취소되는 일이 없다.
+        let mut abort = job.abort.lock().unwrap();
+        let handle = tokio::spawn({
+            let job = job.clone();
+            async move {
+                let Ok(_permit) = permits.acquire_owned().await else {
+                    return;
+                };
+                job.set_status(JobStatus::Running);
+                let result = work(job.clone()).await;
+                job.finish_with(result);
+            }
+        });
+        *abort = Some(handle.abort_handle());
+        id
+    }
+
+    /// drops the oldest finished jobs beyond `keep_finished`.
+    fn evict(&self, jobs: &mut HashMap<u64, Arc<Job>>) {
+        let mut finished: Vec<u64> = jobs
+            .values()
+            .filter(|x| x.status().is_finished())
+            .map(|x| x.summary.lock().unwrap().id)
+            .collect();
+        if finished.len() <= self.keep_finished {
+            return;
+        }
+        finished.sort_unstable();
+        for id in &finished[..finished.len() - self.keep_finished] {
+            jobs.remove(id);
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<Arc<Job>> {
+        self.jobs.lock().unwrap().get(&id).cloned()
+    }
+
+    /// every job, most recent first.
+    pub fn list(&self) -> Vec<JobSummary> {
+        let mut jobs: Vec<JobSummary> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|x| x.summary())
+            .collect();
+        jobs.sort_by_key(|x| std::cmp::Reverse(x.id));
+        jobs
+    }
+
+    /// stops a queued or running job. Requests already sent to a classifier
+    /// worker still complete, their results are dropped. Returns `None` if
+    /// there is no such job, its status otherwise.
+    pub fn cancel(&self, id: u64) -> Option<JobStatus> {
+        let job = self.get(id)?;
+        if let Some(abort) = job.abort.lock().unwrap().take() {
+            abort.abort();
+        }
+        job.set_status(JobStatus::Cancelled);
+        Some(job.summary().status)
+    }
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
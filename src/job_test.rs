@@ -0,0 +1,131 @@
+use crate::job::progress::{Progress, Stage};
+use crate::job::queue::{JobQueue, JobStatus, StageStatus};
+use crate::report::model::{RunOptions, RunReport};
+use crate::storage_test;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+fn options() -> RunOptions {
+    RunOptions {
+        videos: 2,
+        comments: 10,
+        languages: vec!["ko".to_owned()],
+    }
+}
+
+fn report() -> RunReport {
+    RunReport {
+        run_id: None,
+        query: Some("갤럭시".to_owned()),
+        options: options(),
+        started_at: 0,
+        finished_at: 0,
+        videos: Vec::new(),
+        failures: Vec::new(),
+        recommendations: Vec::new(),
+    }
+}
+
+async fn wait_for(queue: &JobQueue, id: u64, status: JobStatus) {
+    for _ in 0..100 {
+        if queue.get(id).unwrap().summary().status == status {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("job {id} never got {status:?}");
+}
+
+#[tokio::test]
+async fn job_queue_progress_test() {
+    let queue = JobQueue::new(1);
+    let (sender, receiver) = oneshot::channel::<()>();
+
+    let first = queue.submit("갤럭시", options(), |job| async move {
+        job.start(Stage::Search, None);
+        job.finish(Stage::Search);
+        job.videos(2);
+        job.start(Stage::Transcript, Some("a"));
+        job.finish(Stage::Transcript);
+        job.add_analysis(Arc::new(storage_test::analysis("Galaxy S23")));
+        receiver.await?;
+        Ok(report())
+    });
+    let second = queue.submit("아이폰", options(), |_| async { Ok(report()) });
+
+    wait_for(&queue, first, JobStatus::Running).await;
+    let summary = queue.get(first).unwrap().summary();
+    let stage = |stage| summary.stages.iter().find(|x| x.stage == stage).unwrap();
+    assert_eq!(stage(Stage::Search).status, StageStatus::Done);
+    assert_eq!(stage(Stage::Transcript).status, StageStatus::Running);
+    assert_eq!(
+        (
+            stage(Stage::Transcript).done,
+            stage(Stage::Transcript).total
+        ),
+        (1, 2)
+    );
+    assert_eq!(
+        summary.current.as_ref().unwrap().video_id.as_deref(),
+        Some("a")
+    );
+    // 분석이 끝난 비디오는 작업이 끝나기 전에 볼 수 있다.
+    assert_eq!(queue.get(first).unwrap().analyses()[0].id, "abc");
+    // 한 번에 하나씩 실행하므로 두 번째 작업은 기다린다.
+    assert_eq!(
+        queue.get(second).unwrap().summary().status,
+        JobStatus::Queued
+    );
+
+    sender.send(()).unwrap();
+    wait_for(&queue, first, JobStatus::Done).await;
+    wait_for(&queue, second, JobStatus::Done).await;
+    assert!(queue.get(first).unwrap().report().is_some());
+    assert_eq!(queue.list()[0].id, second);
+}
+
+#[tokio::test]
+async fn job_queue_cancel_test() {
+    let queue = JobQueue::new(1);
+
+    let running = queue.submit("갤럭시", options(), |_| async {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(report())
+    });
+    let failing = queue.submit("아이폰", options(), |_| async {
+        Err(anyhow::anyhow!("quota exceeded"))
+    });
+
+    wait_for(&queue, running, JobStatus::Running).await;
+    assert_eq!(queue.cancel(running), Some(JobStatus::Cancelled));
+    assert!(queue.get(running).unwrap().report().is_none());
+
+    wait_for(&queue, failing, JobStatus::Failed).await;
+    let summary = queue.get(failing).unwrap().summary();
+    assert_eq!(summary.error.as_deref(), Some("quota exceeded"));
+    // 끝난 작업은 취소해도 상태가 바뀌지 않는다.
+    assert_eq!(queue.cancel(failing), Some(JobStatus::Failed));
+    assert_eq!(queue.cancel(42), None);
+}
+
+#[tokio::test]
+async fn job_queue_retention_test() {
+    let queue = JobQueue::new(1).with_keep_finished(2);
+    for query in ["갤럭시", "아이폰", "픽셀"] {
+        let id = queue.submit(query, options(), |_| async { Ok(report()) });
+        wait_for(&queue, id, JobStatus::Done).await;
+    }
+    let running = queue.submit("아이패드", options(), |_| async {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(report())
+    });
+
+    // 끝난 작업은 최근 두 개만 남고, 실행 중인 작업은 지우지 않는다.
+    assert!(queue.get(1).is_none());
+    assert_eq!(
+        queue.list().iter().map(|x| x.id).collect::<Vec<_>>(),
+        [running, 3, 2]
+    );
+    queue.cancel(running);
+}
@@ -1,331 +1,405 @@
+pub mod cache;
+#[cfg(test)]
+pub mod cache_test;
 pub mod classifier;
 #[cfg(test)]
 pub mod classifier_test;
+pub mod cli;
+pub mod error;
+pub mod fixture;
+#[cfg(test)]
+pub mod fixture_test;
+pub mod job;
+#[cfg(test)]
+pub mod job_test;
+pub mod pipeline;
+#[cfg(test)]
+pub mod pipeline_test;
+pub mod rate_limit;
+#[cfg(test)]
+pub mod rate_limit_test;
+pub mod recommend;
+#[cfg(test)]
+pub mod recommend_test;
+pub mod report;
+#[cfg(test)]
+pub mod report_test;
+pub mod server;
+#[cfg(test)]
+pub mod server_test;
+pub mod storage;
+#[cfg(test)]
+pub mod storage_test;
+pub mod time;
+#[cfg(test)]
+pub mod transcript_test;
+pub mod translate;
+#[cfg(test)]
+pub mod translate_test;
+pub mod youtube_api;
 pub mod youtube_transcript;
 
-use crate::classifier::keyword_extraction_classifier::KeywordExtractionClassifier;
-use crate::classifier::question_answering_classifier::QuestionAnsweringClassifier;
-use crate::classifier::sentiment_classifier::SentimentClassifier;
-use crate::classifier::summarization_classifier::SummarizationClassifier;
-use crate::youtube_transcript::youtube::YoutubeBuilder;
+use crate::cache::Cache;
+use crate::classifier::backend::ClassifierBackend;
+use crate::cli::{Cli, Command, OutputFormat};
+use crate::fixture::recording::Fixtures;
+use crate::fixture::server::{FixtureServer, Mode};
+use crate::job::progress::NoProgress;
+use crate::job::queue::JobQueue;
+use crate::pipeline::{Analyzer, Context};
+use crate::rate_limit::RateLimiter;
+use crate::recommend::aspect::AspectLexicon;
+use crate::report::model::{ReportLine, RunOptions, RunReport, VideoAnalysis};
+use crate::report::writer::{self, Output};
+use crate::report::{html, markdown};
+use crate::server::app;
+use crate::server::state::AppState;
+use crate::storage::store::Store;
+use crate::storage::translator::StoredTranslator;
+use crate::translate::config::TranslatorConfig;
+use crate::translate::translator::Translator;
+use crate::youtube_api::client::YoutubeApiClient;
+use crate::youtube_transcript::config::Config as TranscriptConfig;
+use crate::youtube_transcript::youtube;
+use anyhow::anyhow;
+use clap::Parser;
 use dotenv::dotenv;
-use futures::future::join_all;
-use reqwest::Url;
-use serde_json::Value;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::fmt::Debug;
 use std::io::Write;
-
-/*
-const MAX_RESULTS_VIDEO: usize = 50;
-const MAX_RESULTS_COMMENT: usize = 100;
-*/
-
-// 최대 비디오 수
-const MAX_RESULTS_VIDEO: usize = 10;
-// 최대 댓글 수
-const MAX_RESULTS_COMMENT: usize = 100;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    let input_text = get_input_text();
-    let videos = get_videos(input_text).await.unwrap();
-    println!("{:#?}", videos);
-
-    let scripts = get_scripts(&videos).await.unwrap();
-    let comments = get_comments(&videos).await.unwrap();
-
-    for i in 0..MAX_RESULTS_VIDEO {
-        let video_value = &videos[i];
-        let comments_value = &comments[i];
-
-        let title = video_value["snippet"]["title"]
-            .clone()
-            .as_str()
-            .unwrap()
-            .to_string();
-        let script = scripts[i].clone();
-        let comments: Vec<String> = comments_value
-            .iter()
-            .map(|x| {
-                x["snippet"]["topLevelComment"]["snippet"]["textDisplay"]
-                    .clone()
-                    .as_str()
-                    .unwrap_or_default()
-                    .to_string()
-            })
-            .collect();
-        println!(
-            "제목: {:#?}\n스크립트: {:#?}\n댓글: {:#?}",
-            title, script, comments
-        );
-
-        let translate_script =
-            get_translate_text(script.clone(), "KO".to_owned(), "EN".to_owned()).await;
-        let translate_comments: Vec<String> = join_all(
-            comments
-                .iter()
-                .map(|x| get_translate_text(x.clone(), "KO".to_owned(), "EN".to_owned())),
-        )
-        .await
-        .into_iter()
-        .collect();
-        println!(
-            "영문 스크립트: {:#?}\n영문 댓글: {:#?}",
-            translate_script, translate_comments
-        );
-
-        // 1. 질의응답
-        // DistilBERT 모델을 활용하여 유튜브 스크립트의 주제와 결론에 대해 질문한다.
-        let (_, question_answering_classifier) = QuestionAnsweringClassifier::spawn();
-        let question = "What is the theme and conclusion of the video?".to_owned();
-        let translate_answers = question_answering_classifier
-            .predict(question, translate_script.clone())
-            .await
-            .unwrap();
-        let answers = join_all(translate_answers.iter().flat_map(|x| {
-            x.iter()
-                .map(|y| get_translate_text(y.answer.clone(), "EN".to_owned(), "KO".to_owned()))
-        }))
-        .await;
-        println!("영문 주제와 결론: {translate_answers:#?}");
-        println!("주제와 결론: {answers:#?}");
-
-        // 2. 요약
-        // BART 모델을 활용하여 유튜브 스크립트 요약을 진행한다.
-        let (_, summarization_classifier) = SummarizationClassifier::spawn();
-        let translate_summarize = summarization_classifier
-            .summarize(vec![translate_script.clone()])
-            .await
-            .unwrap();
-        let summarize = join_all(
-            translate_summarize
-                .iter()
-                .map(|x| get_translate_text(x.clone(), "EN".to_owned(), "KO".to_owned())),
-        )
-        .await;
-        println!("영문 스크립트 요약: {translate_summarize:#?}");
-        println!("스크립트 요약: {summarize:#?}");
-
-        // 3. 감정 분석
-        // DistilBERT 모델을 활용하여 유튜브 댓글에 대한 이진 감정을 분석한다.
-        let (_, sentiment_classifier) = SentimentClassifier::spawn();
-        let sentiments = sentiment_classifier
-            .predict(translate_comments.clone())
-            .await
-            .unwrap();
-        println!("댓글 감성 분석: {sentiments:#?}");
-
-        // 4. 키워드 추출
-        // 유튜브 스크립트와 댓글에서 키워드를 추출한다.
-        let (_, keyword_extraction_classifier) = KeywordExtractionClassifier::spawn();
-        let translate_script_keywords = keyword_extraction_classifier
-            .predict(vec![translate_script.clone()])
-            .await
-            .unwrap();
-        let script_keywords = join_all(translate_script_keywords.iter().flat_map(|x| {
-            x.iter()
-                .map(|y| get_translate_text(y.text.clone(), "EN".to_owned(), "KO".to_owned()))
-        }))
-        .await;
-        println!("영문 스크립트 키워드: {translate_script_keywords:#?}");
-        println!("스크립트 키워드: {script_keywords:#?}");
+    // 자막 설정은 비디오마다 읽으므로 잘못된 값은 시작할 때 알린다.
+    youtube_transcript::config::init(TranscriptConfig::from_env()?);
+    run(Cli::parse()).await
+}
 
-        let translate_comments_keywords = keyword_extraction_classifier
-            .predict(translate_comments.clone())
-            .await
-            .unwrap();
-        let comments_keywords = join_all(translate_comments_keywords.iter().flat_map(|x| {
-            x.iter()
-                .map(|y| get_translate_text(y.text.clone(), "EN".to_owned(), "KO".to_owned()))
-        }))
-        .await;
-        println!("영문 댓글 키워드: {translate_comments_keywords:#?}");
-        println!("댓글 키워드: {comments_keywords:#?}");
+/// runs the command given on the command line.
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    match &cli.command {
+        Command::Search { query } => search(&cli, query).await,
+        Command::Transcript { video_id } => transcript(&cli, video_id).await,
+        Command::Comments { video_id } => comments(&cli, video_id).await,
+        Command::Analyze { video_id } => analyze(&cli, None, std::slice::from_ref(video_id)).await,
+        Command::Recommend { query } => recommend(&cli, query).await,
+        Command::Serve {
+            addr,
+            jobs,
+            keep_jobs,
+        } => serve(&cli, *addr, *jobs, *keep_jobs).await,
+        Command::History { product, limit } => history(&cli, product.as_deref(), *limit),
+        Command::Rerun { run_id } => rerun(&cli, *run_id).await,
+        Command::Fixtures {
+            addr,
+            dir,
+            record,
+            upstream,
+        } => fixtures(*addr, dir, *record, upstream).await,
     }
 }
 
-fn get_input_text() -> String {
-    print!("어떤 제품을 추천 받으시겠습니까?: ");
-    std::io::stdout().flush().unwrap();
-
-    let mut buf = String::new();
-    std::io::stdin().read_line(&mut buf).unwrap();
-    buf.trim().to_string()
+async fn search(cli: &Cli, query: &str) -> anyhow::Result<()> {
+    let youtube_api = youtube_api(cli)?;
+    let videos = pipeline::get_videos(&youtube_api, query, cli.videos).await?;
+    print(cli.format, &videos)
 }
 
-async fn get_videos(input_text: String) -> Option<Vec<Value>> {
-    let youtube_api_key = std::env::var("YOUTUBE_API_KEY").unwrap();
-    let client = reqwest::Client::new();
-
-    let mut page_token = String::new();
-    let mut videos = Vec::new();
-
-    loop {
-        let params: HashMap<&str, String> = [
-            ("key", youtube_api_key.clone()),
-            ("maxResults", MAX_RESULTS_VIDEO.to_string()),
-            ("part", "snippet".to_owned()),
-            ("pageToken", page_token.clone()),
-            ("q", input_text.to_owned()),
-            ("type", "video".to_owned()),
-        ]
-        .into();
-        let url =
-            Url::parse_with_params("https://www.googleapis.com/youtube/v3/search", params).unwrap();
-        let response = client.get(url).send().await.unwrap();
-
-        if !response.status().is_success() {
-            println!("status: {}", response.status());
-            println!("text: {}", response.text().await.unwrap());
-            return Some(Vec::new());
-        }
-
-        let json = response.json::<Value>().await.unwrap();
-
-        if let Some(error) = json.get("error") {
-            println!("error: {error:#?}");
-            return Some(Vec::new());
+async fn transcript(cli: &Cli, video_id: &str) -> anyhow::Result<()> {
+    let transcript = pipeline::get_transcript(
+        &cache(cli),
+        &rate_limiter(cli),
+        &web_url(cli),
+        &cli.languages,
+        video_id,
+    )
+    .await?;
+
+    match cli.format {
+        OutputFormat::Text => {
+            println!("자막: {:#?}", transcript.caption);
+            print!("{}", String::from(transcript));
+            Ok(())
         }
-
-        if let Some(items) = json["items"].as_array() {
-            videos.extend(items.clone());
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&transcript)?);
+            Ok(())
         }
-
-        if videos.len() >= MAX_RESULTS_VIDEO {
-            return Some(videos);
-        } else if let Some(next_page_token) = json["nextPageToken"].as_str() {
-            page_token = next_page_token.to_string();
-        } else {
-            return Some(videos);
+        OutputFormat::Jsonl => {
+            println!("{}", serde_json::to_string(&transcript)?);
+            Ok(())
         }
+        _ => Err(anyhow!(
+            "{:?} is only supported by analyze and recommend",
+            cli.format
+        )),
     }
 }
 
-async fn get_scripts(videos: &Vec<Value>) -> Option<Vec<String>> {
-    let youtube_loader = YoutubeBuilder::default();
-    let youtube_loader = youtube_loader.build();
+async fn comments(cli: &Cli, video_id: &str) -> anyhow::Result<()> {
+    let youtube_api = youtube_api(cli)?;
+    let comments = pipeline::get_comments(&youtube_api, video_id, cli.comments).await?;
 
-    let mut scripts = Vec::new();
+    match cli.format {
+        OutputFormat::Text => {
+            let comments: Vec<&str> = comments.iter().map(|x| x.text()).collect();
+            print(cli.format, &comments)
+        }
+        _ => print(cli.format, &comments),
+    }
+}
 
-    for video in videos {
-        let id = video["id"]["videoId"].clone();
-        let transcript = youtube_loader
-            .transcript(("https://www.youtube.com/watch?v=".to_owned() + id.as_str()?).as_str())
-            .await
-            .unwrap();
+async fn recommend(cli: &Cli, query: &str) -> anyhow::Result<()> {
+    let youtube_api = youtube_api(cli)?;
+    let ids = pipeline::search_video_ids(&youtube_api, query, cli.videos).await?;
+    analyze(cli, Some(query), &ids).await
+}
 
-        let script = transcript
-            .transcripts
-            .into_iter()
-            .map(|x| x.text)
-            .collect::<Vec<String>>()
-            .join(" ");
-        scripts.push(script);
+/// serves the pipeline over http, the global flags being the default options.
+async fn serve(cli: &Cli, addr: SocketAddr, jobs: usize, keep_jobs: usize) -> anyhow::Result<()> {
+    let state = Arc::new(AppState {
+        youtube_api: youtube_api(cli)?,
+        analyzer: Analyzer::new(
+            translator(cli).await?,
+            classifier(cli)?,
+            aspect_lexicon(cli)?,
+            false,
+        ),
+        store: store(cli)?,
+        options: RunOptions {
+            videos: cli.videos,
+            comments: cli.comments,
+            languages: cli.languages.clone(),
+        },
+        jobs: JobQueue::new(jobs).with_keep_finished(keep_jobs),
+    });
+    app::serve(addr, state.clone()).await?;
+
+    // 진행 중인 작업이 없으면 분류기를 정리한다.
+    if let Ok(state) = Arc::try_unwrap(state) {
+        state.analyzer.shutdown().await?;
     }
-
-    Some(scripts)
+    Ok(())
 }
 
-async fn get_comments(videos: &Vec<Value>) -> Option<Vec<Vec<Value>>> {
-    let youtube_api_key = std::env::var("YOUTUBE_API_KEY").unwrap();
-    let client = reqwest::Client::new();
+/// serves the recorded responses, or records new ones with `record`.
+async fn fixtures(
+    addr: SocketAddr,
+    dir: &Path,
+    record: bool,
+    upstreams: &[(String, String)],
+) -> anyhow::Result<()> {
+    let mode = if record { Mode::Record } else { Mode::Replay };
+    let mut server = FixtureServer::new(Fixtures::new(dir), mode);
+    for (name, url) in upstreams {
+        server = server.upstream(name, url);
+    }
+    server.serve(addr).await
+}
 
-    let mut comments = Vec::new();
+/// analyzes the videos and ranks the products they talk about.
+async fn analyze(cli: &Cli, query: Option<&str>, ids: &[String]) -> anyhow::Result<()> {
+    let youtube_api = youtube_api(cli)?;
+    let store = store(cli)?;
+    let analyzer = Analyzer::new(
+        translator(cli).await?,
+        classifier(cli)?,
+        aspect_lexicon(cli)?,
+        cli.format == OutputFormat::Text,
+    );
+    let mut output = output(cli)?;
+
+    let options = RunOptions {
+        videos: cli.videos,
+        comments: cli.comments,
+        languages: cli.languages.clone(),
+    };
+    let context = Context {
+        youtube_api: &youtube_api,
+        analyzer: &analyzer,
+        store: store.as_ref(),
+        progress: &NoProgress,
+    };
+    let report = pipeline::run(&context, query, ids, options, |analysis| {
+        write_video(cli, &mut output, analysis)
+    })
+    .await;
+    analyzer.shutdown().await?;
+
+    write_report(cli, &mut output, &report?)?;
+    output.finish()
+}
 
-    for video in videos {
-        let mut page_token = String::new();
-        let mut comment = Vec::new();
-        let id = video["id"]["videoId"].clone();
+/// runs the models again over the text stored by a previous run.
+async fn rerun(cli: &Cli, run_id: i64) -> anyhow::Result<()> {
+    let youtube_api = youtube_api(cli)?;
+    let store = store(cli)?.ok_or_else(|| anyhow!("rerun reads the store, drop --no-store"))?;
+    // 저장된 번역을 먼저 쓰고, 없는 것만 번역기에 보낸다.
+    let translator = StoredTranslator::new(store.clone(), translator(cli).await?);
+    let analyzer = Analyzer::new(
+        Box::new(translator),
+        classifier(cli)?,
+        aspect_lexicon(cli)?,
+        cli.format == OutputFormat::Text,
+    );
+    let mut output = output(cli)?;
+
+    let context = Context {
+        youtube_api: &youtube_api,
+        analyzer: &analyzer,
+        store: Some(&store),
+        progress: &NoProgress,
+    };
+    let report = pipeline::rerun(&context, run_id, |analysis| {
+        write_video(cli, &mut output, analysis)
+    })
+    .await;
+    analyzer.shutdown().await?;
+
+    write_report(cli, &mut output, &report?)?;
+    output.finish()
+}
 
-        loop {
-            let params: HashMap<&str, String> = [
-                ("key", youtube_api_key.clone()),
-                ("maxResults", MAX_RESULTS_COMMENT.to_string()),
-                ("part", "snippet".to_owned()),
-                ("pageToken", page_token.clone()),
-                ("video_id", id.as_str()?.to_string()),
-            ]
-            .into();
-            let url = Url::parse_with_params(
-                "https://www.googleapis.com/youtube/v3/commentThreads",
-                params,
-            )
-            .unwrap();
-            let response = client.get(url).send().await.unwrap();
+/// lists the stored runs, or the rank of a product across them.
+fn history(cli: &Cli, product: Option<&str>, limit: usize) -> anyhow::Result<()> {
+    let store = store(cli)?.ok_or_else(|| anyhow!("history reads the store, drop --no-store"))?;
+    match product {
+        Some(product) => print(cli.format, &store.product_history(product)?),
+        None => print(cli.format, &store.runs(limit)?),
+    }
+}
 
-            if !response.status().is_success() {
-                println!("status: {}", response.status());
-                println!("text: {}", response.text().await.unwrap());
-                comments.push(Vec::new());
-                break;
-            }
+/// json lines 형식은 분석이 끝난 비디오부터 바로 쓴다.
+fn write_video(cli: &Cli, output: &mut impl Write, analysis: &VideoAnalysis) -> anyhow::Result<()> {
+    if cli.format == OutputFormat::Jsonl {
+        writer::write_json_line(output, &ReportLine::Video(analysis))?;
+    }
+    Ok(())
+}
 
-            let json = response.json::<Value>().await.unwrap();
+fn write_report(cli: &Cli, output: &mut impl Write, report: &RunReport) -> anyhow::Result<()> {
+    match cli.format {
+        OutputFormat::Text => {
+            writeln!(output, "추천 순위: {:#?}", report.recommendations)?;
+            Ok(())
+        }
+        OutputFormat::Json => writer::write_json(output, report),
+        OutputFormat::Jsonl => writer::write_json_line(
+            output,
+            &ReportLine::Recommendations {
+                query: report.query.as_deref(),
+                recommendations: &report.recommendations,
+            },
+        ),
+        OutputFormat::Html => writer::write_text(output, &html::render(report)),
+        OutputFormat::Markdown => writer::write_text(output, &markdown::render(report)),
+    }
+}
 
-            if let Some(error) = json.get("error") {
-                println!("error: {error:#?}");
-                comments.push(Vec::new());
-                break;
-            }
+/// the translator given with `--translator`, the one of the environment otherwise.
+async fn translator(cli: &Cli) -> anyhow::Result<Box<dyn Translator>> {
+    let mut translator_config = TranslatorConfig::from_env()?;
+    if let Some(backend) = cli.translator {
+        translator_config.backend = backend;
+    }
+    Ok(translator_config.build_cached(cache(cli)).await?)
+}
 
-            if let Some(items) = json["items"].as_array() {
-                comment.extend(items.clone());
-            }
+/// the classifier given with `--classifier`, the one of the environment otherwise.
+fn classifier(cli: &Cli) -> anyhow::Result<ClassifierBackend> {
+    match cli.classifier {
+        Some(backend) => Ok(backend),
+        None => Ok(ClassifierBackend::from_env()?),
+    }
+}
 
-            if comment.len() >= MAX_RESULTS_COMMENT {
-                comments.push(comment);
-                break;
-            } else if let Some(next_page_token) = json["nextPageToken"].as_str() {
-                page_token = next_page_token.to_string();
-            } else {
-                comments.push(comment);
-                break;
-            }
-        }
+/// the api key and urls given on the command line, the ones of the
+/// environment otherwise.
+/// the aspects of the file given with `--aspects`, the one of the environment otherwise.
+fn aspect_lexicon(cli: &Cli) -> anyhow::Result<AspectLexicon> {
+    match &cli.aspects {
+        Some(path) => AspectLexicon::from_file(path),
+        None => AspectLexicon::from_env(),
     }
+}
 
-    Some(comments)
+fn youtube_api(cli: &Cli) -> anyhow::Result<YoutubeApiClient> {
+    let mut youtube_api = match &cli.youtube_api_key {
+        Some(api_key) => YoutubeApiClient::new(api_key.as_str()).with_env_urls(),
+        None => YoutubeApiClient::from_env()?,
+    };
+    if let Some(url) = &cli.youtube_api_url {
+        youtube_api = youtube_api.with_base_url(url.as_str());
+    }
+    Ok(youtube_api
+        .with_web_url(web_url(cli))
+        .with_cache(cache(cli))
+        .with_rate_limiter(rate_limiter(cli))
+        .with_concurrency(cli.concurrency))
 }
 
-async fn get_translate_text(text: String, source: String, target: String) -> String {
-    let deepl_api_key = std::env::var("DEEPL_API_KEY").unwrap();
-    let client = reqwest::Client::new();
+/// the site given with `--youtube-url`, the one of `YOUTUBE_URL` otherwise.
+fn web_url(cli: &Cli) -> String {
+    cli.youtube_url
+        .clone()
+        .unwrap_or_else(youtube::base_url_from_env)
+}
 
-    let body: HashMap<&str, Value> = [
-        ("text", [text].into()),
-        ("source_lang", source.into()),
-        ("target_lang", target.into()),
-    ]
-    .into();
+/// the default limits of YouTube, with the ones given with `--rate-limit`.
+fn rate_limiter(cli: &Cli) -> RateLimiter {
+    cli.rate_limit
+        .iter()
+        .fold(RateLimiter::default(), |rate_limiter, (host, interval)| {
+            rate_limiter.with_host(host, *interval)
+        })
+}
 
-    let url = Url::parse("https://api-free.deepl.com/v2/translate").unwrap();
-    let response = client
-        .post(url)
-        .header(
-            "Authorization",
-            "DeepL-Auth-Key ".to_string() + deepl_api_key.as_str(),
-        )
-        .json(&body)
-        .send()
-        .await
-        .unwrap();
+/// the database given with `--db`, the one of `DATABASE_PATH` otherwise.
+/// `None` with `--no-store`.
+fn store(cli: &Cli) -> anyhow::Result<Option<Store>> {
+    if cli.no_store {
+        return Ok(None);
+    }
+    Ok(Some(match &cli.db {
+        Some(path) => Store::open(path)?,
+        None => Store::from_env()?,
+    }))
+}
 
-    if !response.status().is_success() {
-        println!("status: {}", response.status());
-        println!("text: {}", response.text().await.unwrap());
-        return String::new();
+/// the cache of `CACHE_DIR`, disabled by `--no-cache`.
+fn cache(cli: &Cli) -> Cache {
+    if cli.no_cache {
+        Cache::disabled()
+    } else {
+        Cache::from_env()
     }
+}
 
-    let json = response.json::<Value>().await.unwrap();
+/// the file given with `--output`, the standard output otherwise.
+fn output(cli: &Cli) -> anyhow::Result<Output> {
+    match &cli.output {
+        // 비디오마다 쓰는 기록은 실행이 실패해도 남긴다.
+        Some(path) if cli.format == OutputFormat::Jsonl => Output::stream(path),
+        Some(path) => Output::create(path),
+        None => Ok(Output::stdout()),
+    }
+}
 
-    if let Some(translations) = json["translations"].as_array() {
-        translations[0]["text"]
-            .clone()
-            .as_str()
-            .unwrap()
-            .to_string()
-    } else {
-        String::new()
+fn print<T: Serialize + Debug>(format: OutputFormat, value: &T) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Text => println!("{:#?}", value),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(value)?),
+        _ => {
+            return Err(anyhow!(
+                "{:?} is only supported by analyze and recommend",
+                format
+            ))
+        }
     }
+    Ok(())
 }
@@ -0,0 +1,847 @@
+use crate::cache::Cache;
+use crate::classifier::backend::ClassifierBackend;
+use crate::classifier::chunking::{self, MAX_CHUNK_TOKENS};
+use crate::classifier::classifier_hub::ClassifierHub;
+use crate::classifier::keyword_extraction_classifier::Keyword;
+use crate::error::{Error, Result};
+use crate::job::progress::{Progress, Stage};
+use crate::rate_limit::RateLimiter;
+use crate::recommend::aspect::{self, AspectLexicon, AspectSentiment};
+use crate::recommend::product::{Mention, ProductNormalizer};
+use crate::recommend::recommender::{Recommender, VideoSignals};
+use crate::report::model::{
+    CommentSentiment, KeywordScore, QaAnswer, RunOptions, RunReport, ScriptChunk,
+    TranscriptOutcome, VideoAnalysis, VideoFailure,
+};
+use crate::storage::store::Store;
+use crate::time::unix_time;
+use crate::translate::translator::Translator;
+use crate::youtube_api::client::YoutubeApiClient;
+use crate::youtube_api::model::{CommentThread, SearchResult, Video};
+use crate::youtube_transcript::error::Error as TranscriptError;
+use crate::youtube_transcript::parser::Transcript;
+use crate::youtube_transcript::youtube::YoutubeBuilder;
+use anyhow::anyhow;
+use futures::stream::{self, StreamExt};
+use rust_bert::pipelines::sentiment::Sentiment;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+// 단계 사이의 채널에 쌓아 둘 비디오 수
+const STAGE_CAPACITY: usize = 2;
+
+/// searches videos and fetches their snippet and statistics.
+pub async fn get_videos(
+    youtube_api: &YoutubeApiClient,
+    query: &str,
+    max_results: usize,
+) -> Result<Vec<Video>> {
+    let ids = search_video_ids(youtube_api, query, max_results).await?;
+    get_video_details(youtube_api, &ids).await
+}
+
+/// ids of the videos found for the query, in the order of the search.
+pub async fn search_video_ids(
+    youtube_api: &YoutubeApiClient,
+    query: &str,
+    max_results: usize,
+) -> Result<Vec<String>> {
+    let results = youtube_api.search(query, max_results).await?;
+    Ok(results
+        .iter()
+        .filter_map(SearchResult::video_id)
+        .map(str::to_string)
+        .collect())
+}
+
+/// fetches snippet and statistics of the videos, in the order of `ids`.
+pub async fn get_video_details(
+    youtube_api: &YoutubeApiClient,
+    ids: &[String],
+) -> Result<Vec<Video>> {
+    let mut details = youtube_api.videos(ids).await?;
+    details.sort_by_key(|x| ids.iter().position(|id| *id == x.id));
+    Ok(details)
+}
+
+/// fetches the transcript of a video in the first available language from
+/// the site at `base_url`, Ex: "https://www.youtube.com".
+pub async fn get_transcript(
+    cache: &Cache,
+    rate_limiter: &RateLimiter,
+    base_url: &str,
+    languages: &[String],
+    video_id: &str,
+) -> Result<Transcript> {
+    let youtube_loader = YoutubeBuilder::default()
+        .languages(languages)
+        .cache(cache.clone())
+        .rate_limiter(rate_limiter.clone())
+        .base_url(base_url);
+    let youtube_loader = youtube_loader.build();
+
+    Ok(youtube_loader
+        .transcript(&youtube_loader.watch_url(video_id))
+        .await?)
+}
+
+/// the transcript to analyze and what happened fetching it. A transcript that
+/// couldn't be fetched or read is left empty.
+pub fn transcript_outcome(transcript: Result<Transcript>) -> (Transcript, TranscriptOutcome) {
+    match transcript {
+        Ok(transcript) if transcript.caption.is_some() => (transcript, TranscriptOutcome::Ok),
+        Ok(transcript) => (transcript, TranscriptOutcome::NoCaptions),
+        Err(error) => {
+            let outcome = match error {
+                Error::TranscriptUnavailable(TranscriptError::ParseError(reason)) => {
+                    TranscriptOutcome::ParseFailed { reason }
+                }
+                Error::TranscriptUnavailable(error) => TranscriptOutcome::Unavailable {
+                    reason: error.to_string(),
+                },
+                error => TranscriptOutcome::Unavailable {
+                    reason: error.to_string(),
+                },
+            };
+            (Transcript::default(), outcome)
+        }
+    }
+}
+
+/// fetches the comment threads of a video, none if comments are disabled.
+pub async fn get_comments(
+    youtube_api: &YoutubeApiClient,
+    video_id: &str,
+    max_results: usize,
+) -> Result<Vec<CommentThread>> {
+    match youtube_api.comment_threads(video_id, max_results).await {
+        Err(Error::CommentsDisabled(_)) => Ok(Vec::new()),
+        comments => comments,
+    }
+}
+
+/// fetches the transcript and the comment threads of a video at the same time.
+/// Only the comments fail the video, the transcript comes with its outcome.
+pub async fn fetch_video(
+    youtube_api: &YoutubeApiClient,
+    options: &RunOptions,
+    video_id: &str,
+    progress: &dyn Progress,
+) -> Result<(Transcript, TranscriptOutcome, Vec<CommentThread>)> {
+    let transcript = async {
+        progress.start(Stage::Transcript, Some(video_id));
+        let transcript = get_transcript(
+            youtube_api.cache(),
+            youtube_api.rate_limiter(),
+            youtube_api.web_url(),
+            &options.languages,
+            video_id,
+        )
+        .await;
+        progress.finish(Stage::Transcript);
+        transcript_outcome(transcript)
+    };
+    let comment_threads = async {
+        progress.start(Stage::Comments, Some(video_id));
+        let comment_threads = get_comments(youtube_api, video_id, options.comments).await;
+        progress.finish(Stage::Comments);
+        comment_threads
+    };
+
+    let ((transcript, transcript_outcome), comment_threads) =
+        tokio::join!(transcript, comment_threads);
+    Ok((transcript, transcript_outcome, comment_threads?))
+}
+
+/// What a run works with.
+pub struct Context<'a> {
+    pub youtube_api: &'a YoutubeApiClient,
+    pub analyzer: &'a Analyzer,
+    /// keeps the fetched data and the results of the run when given
+    pub store: Option<&'a Store>,
+    pub progress: &'a dyn Progress,
+}
+
+/// analyzes the videos in the order of `ids` and ranks the products they talk
+/// about. Each video goes through the stages as soon as its transcript and
+/// comments arrive, `on_video` is called as soon as it is analyzed.
+pub async fn run(
+    context: &Context<'_>,
+    query: Option<&str>,
+    ids: &[String],
+    options: RunOptions,
+    on_video: impl FnMut(&Arc<VideoAnalysis>) -> anyhow::Result<()>,
+) -> anyhow::Result<RunReport> {
+    let Context {
+        youtube_api,
+        store,
+        progress,
+        ..
+    } = *context;
+    let started_at = unix_time();
+    let videos = get_video_details(youtube_api, ids).await?;
+    if videos.is_empty() {
+        return Err(anyhow!("no video found: {}", ids.join(", ")));
+    }
+    progress.videos(videos.len());
+
+    let run_id = match store {
+        Some(store) => {
+            store.save_videos(&videos)?;
+            let mut channel_ids: Vec<String> = videos
+                .iter()
+                .map(|x| x.snippet.channel_id.clone())
+                .collect();
+            channel_ids.sort();
+            channel_ids.dedup();
+            // channels.list 할당량을 아끼려고 저장되지 않은 채널만 가져온다.
+            let missing = store.missing_channels(&channel_ids)?;
+            if !missing.is_empty() {
+                store.save_channels(&youtube_api.channels(&missing).await?)?;
+            }
+            Some(store.start_run(query, &options)?)
+        }
+        None => None,
+    };
+
+    // 비디오 여러 개를 한꺼번에 가져와 가져온 순서가 아닌 검색 순서대로 다음 단계로 넘긴다.
+    let (fetched_tx, fetched_rx) = mpsc::channel(STAGE_CAPACITY);
+    let fetches: Vec<_> = videos
+        .iter()
+        .map(|video| fetch_video(youtube_api, &options, &video.id, progress))
+        .collect();
+    let videos = &videos;
+    let fetch = async move {
+        let mut fetches = stream::iter(fetches).buffered(youtube_api.concurrency());
+        for video in videos {
+            let Some(fetched) = fetches.next().await else {
+                break;
+            };
+            // 한 비디오를 가져오지 못해도 나머지 비디오는 분석한다.
+            let fetched = match fetched {
+                Ok((transcript, transcript_outcome, comment_threads)) => {
+                    if let Some((store, run_id)) = store.zip(run_id) {
+                        // 읽지 못한 자막으로 저장된 자막을 덮어쓰지 않는다.
+                        if matches!(
+                            transcript_outcome,
+                            TranscriptOutcome::Ok | TranscriptOutcome::NoCaptions
+                        ) {
+                            store.save_transcript(&video.id, &transcript)?;
+                        }
+                        store.save_comments(run_id, &video.id, &comment_threads)?;
+                    }
+                    Ok(FetchedVideo {
+                        video: video.clone(),
+                        transcript,
+                        transcript_outcome,
+                        comments: comment_threads
+                            .iter()
+                            .map(|x| x.text().to_string())
+                            .collect(),
+                    })
+                }
+                Err(error) => Err(VideoFailure {
+                    id: video.id.clone(),
+                    title: video.snippet.title.clone(),
+                    error: error.to_string(),
+                }),
+            };
+            if fetched_tx.send(fetched).await.is_err() {
+                break;
+            }
+        }
+        anyhow::Ok(())
+    };
+
+    let (analyses, failures) = analyze_stages(context, fetch, fetched_rx, run_id, on_video).await?;
+    finish(
+        store.zip(run_id),
+        query,
+        options,
+        started_at,
+        analyses,
+        failures,
+    )
+}
+
+/// runs the models again over the videos, transcripts and comments stored by
+/// a previous run, without fetching anything, and records a new run.
+pub async fn rerun(
+    context: &Context<'_>,
+    run_id: i64,
+    on_video: impl FnMut(&Arc<VideoAnalysis>) -> anyhow::Result<()>,
+) -> anyhow::Result<RunReport> {
+    let Context {
+        store, progress, ..
+    } = *context;
+    let store = store.ok_or_else(|| anyhow!("rerun needs a store"))?;
+    let (query, options) = store
+        .run(run_id)?
+        .ok_or_else(|| anyhow!("no run {}", run_id))?;
+    let started_at = unix_time();
+    let ids = store.run_videos(run_id)?;
+    progress.videos(ids.len());
+    let new_run_id = store.start_run(query.as_deref(), &options)?;
+    // 새 실행도 원래 실행과 같은 댓글을 읽는다.
+    store.copy_run_comments(run_id, new_run_id)?;
+
+    let (fetched_tx, fetched_rx) = mpsc::channel(STAGE_CAPACITY);
+    let ids = &ids;
+    let options = &options;
+    let fetch = async move {
+        for id in ids {
+            let video = store
+                .video(id)?
+                .ok_or_else(|| anyhow!("video {} is not stored", id))?;
+
+            progress.start(Stage::Transcript, Some(id));
+            let (transcript, transcript_outcome) = match store.transcript(id)? {
+                Some(transcript) => transcript_outcome(Ok(transcript)),
+                None => (
+                    Transcript::default(),
+                    TranscriptOutcome::Unavailable {
+                        reason: "no transcript stored".to_owned(),
+                    },
+                ),
+            };
+            progress.finish(Stage::Transcript);
+
+            progress.start(Stage::Comments, Some(id));
+            let comments = store.comments(run_id, id, options.comments)?;
+            progress.finish(Stage::Comments);
+
+            let fetched = FetchedVideo {
+                video,
+                transcript,
+                transcript_outcome,
+                comments,
+            };
+            if fetched_tx.send(Ok(fetched)).await.is_err() {
+                break;
+            }
+        }
+        anyhow::Ok(())
+    };
+
+    let (analyses, failures) =
+        analyze_stages(context, fetch, fetched_rx, Some(new_run_id), on_video).await?;
+    finish(
+        Some((store, new_run_id)),
+        query.as_deref(),
+        options.clone(),
+        started_at,
+        analyses,
+        failures,
+    )
+}
+
+/// Video with the transcript and comments to analyze it with, or why they
+/// couldn't be fetched.
+type Fetched = std::result::Result<FetchedVideo, VideoFailure>;
+
+/// Video of a run with its transcript and comments.
+struct FetchedVideo {
+    video: Video,
+    transcript: Transcript,
+    transcript_outcome: TranscriptOutcome,
+    comments: Vec<String>,
+}
+
+/// runs `fetch` and the analysis stages at the same time, each stage handing
+/// the videos to the next one through a channel as soon as it is done with
+/// them: `fetch` sends them to the translation, then the models, then the
+/// analyses are stored and given to `on_video`. The first failing stage fails
+/// the run, the others stop once their channel closes.
+async fn analyze_stages(
+    context: &Context<'_>,
+    fetch: impl Future<Output = anyhow::Result<()>>,
+    mut fetched_rx: mpsc::Receiver<Fetched>,
+    run_id: Option<i64>,
+    mut on_video: impl FnMut(&Arc<VideoAnalysis>) -> anyhow::Result<()>,
+) -> anyhow::Result<(Vec<Arc<VideoAnalysis>>, Vec<VideoFailure>)> {
+    let Context {
+        analyzer,
+        store,
+        progress,
+        ..
+    } = *context;
+    let (translated_tx, mut translated_rx) = mpsc::channel(STAGE_CAPACITY);
+    let (analyzed_tx, mut analyzed_rx) = mpsc::channel(STAGE_CAPACITY);
+
+    let translate = async move {
+        while let Some(fetched) = fetched_rx.recv().await {
+            let translated = match fetched {
+                Ok(x) => Ok(analyzer
+                    .translate(
+                        x.video,
+                        x.transcript,
+                        x.transcript_outcome,
+                        x.comments,
+                        progress,
+                    )
+                    .await?),
+                Err(failure) => {
+                    skip_analysis(progress);
+                    Err(failure)
+                }
+            };
+            if translated_tx.send(translated).await.is_err() {
+                break;
+            }
+        }
+        anyhow::Ok(())
+    };
+
+    let classify = async move {
+        while let Some(translated) = translated_rx.recv().await {
+            let analyzed = match translated {
+                Ok(translated) => Ok(analyzer.classify(translated, progress).await?),
+                Err(failure) => Err(failure),
+            };
+            if analyzed_tx.send(analyzed).await.is_err() {
+                break;
+            }
+        }
+        anyhow::Ok(())
+    };
+
+    let aggregate = async move {
+        let mut analyses = Vec::new();
+        let mut failures = Vec::new();
+        while let Some(analyzed) = analyzed_rx.recv().await {
+            match analyzed {
+                Ok(analysis) => {
+                    if let (Some(store), Some(run_id)) = (store, run_id) {
+                        store.save_analysis(run_id, &analysis)?;
+                    }
+                    let analysis = Arc::new(analysis);
+                    on_video(&analysis)?;
+                    analyses.push(analysis);
+                }
+                Err(failure) => failures.push(failure),
+            }
+        }
+        anyhow::Ok((analyses, failures))
+    };
+
+    let (fetch, translate, classify, aggregate) =
+        tokio::join!(fetch, translate, classify, aggregate);
+    fetch?;
+    translate?;
+    classify?;
+    aggregate
+}
+
+/// counts the analysis stages of a video that couldn't be fetched as done, so
+/// that the stages still reach the number of videos.
+fn skip_analysis(progress: &dyn Progress) {
+    for stage in Stage::ALL {
+        if stage.per_video() && !matches!(stage, Stage::Transcript | Stage::Comments) {
+            progress.finish(stage);
+        }
+    }
+}
+
+/// ranks the products of the analyses and records the end of the run.
+fn finish(
+    store: Option<(&Store, i64)>,
+    query: Option<&str>,
+    options: RunOptions,
+    started_at: u64,
+    analyses: Vec<Arc<VideoAnalysis>>,
+    failures: Vec<VideoFailure>,
+) -> anyhow::Result<RunReport> {
+    let signals: Vec<VideoSignals> = analyses.iter().map(|x| x.signals.clone()).collect();
+    let report = RunReport {
+        run_id: store.map(|(_, run_id)| run_id),
+        query: query.map(str::to_string),
+        options,
+        started_at,
+        finished_at: unix_time(),
+        videos: analyses,
+        failures,
+        recommendations: Recommender::default().rank(&signals),
+    };
+    if let Some((store, run_id)) = store {
+        store.finish_run(run_id, &report)?;
+    }
+    Ok(report)
+}
+
+/// Video with its script chunks and comments translated, between the two
+/// stages of the analysis.
+pub struct Translated {
+    pub video: Video,
+    pub transcript: Transcript,
+    pub transcript_outcome: TranscriptOutcome,
+    pub script: String,
+    pub comments: Vec<String>,
+    pub chunks: Vec<ScriptChunk>,
+    pub translated_comments: Vec<String>,
+}
+
+/// Runs the translation and the models over a video.
+pub struct Analyzer {
+    translator: Box<dyn Translator>,
+    classifier_hub: ClassifierHub,
+    product_normalizer: ProductNormalizer,
+    aspect_lexicon: AspectLexicon,
+    /// prints every intermediate result
+    verbose: bool,
+}
+
+impl Analyzer {
+    /// creates [`Analyzer`], spawning the classifiers of `backend`. The
+    /// sentiment of the comments is summarized per aspect of `aspect_lexicon`.
+    pub fn new(
+        translator: Box<dyn Translator>,
+        backend: ClassifierBackend,
+        aspect_lexicon: AspectLexicon,
+        verbose: bool,
+    ) -> Self {
+        Self {
+            translator,
+            classifier_hub: ClassifierHub::spawn(backend),
+            product_normalizer: ProductNormalizer::default(),
+            aspect_lexicon,
+            verbose,
+        }
+    }
+
+    /// splits the korean script of a video into chunks and translates them
+    /// along with the comments, the first stage of the analysis.
+    pub async fn translate(
+        &self,
+        video: Video,
+        transcript: Transcript,
+        transcript_outcome: TranscriptOutcome,
+        comments: Vec<String>,
+        progress: &dyn Progress,
+    ) -> anyhow::Result<Translated> {
+        let video_id = Some(video.id.as_str());
+        let script = transcript.script();
+        let translator = &self.translator;
+        if self.verbose {
+            println!(
+                "제목: {:#?}\n스크립트: {:#?}\n댓글: {:#?}",
+                video.snippet.title, script, comments
+            );
+        }
+
+        // 모델이 입력을 자르지 않도록 자막 조각 경계에서 스크립트를 나누어 번역한다.
+        progress.start(Stage::Translate, video_id);
+        let texts: Vec<&str> = transcript
+            .transcripts
+            .iter()
+            .map(|x| x.text.as_str())
+            .collect();
+        let ranges = chunking::chunks(&texts, MAX_CHUNK_TOKENS);
+        let chunk_texts: Vec<String> = ranges
+            .iter()
+            .map(|range| texts[range.clone()].join(" "))
+            .collect();
+        let translate_chunks = translator.translate(&chunk_texts, "KO", "EN").await?;
+        let chunks: Vec<ScriptChunk> = ranges
+            .into_iter()
+            .zip(chunk_texts)
+            .zip(translate_chunks)
+            .map(|((range, text), translated_text)| ScriptChunk {
+                start: transcript.transcripts[range.start].start,
+                text,
+                translated_text,
+            })
+            .collect();
+        let translate_comments = translator.translate(&comments, "KO", "EN").await?;
+        progress.finish(Stage::Translate);
+        if self.verbose {
+            println!(
+                "영문 스크립트: {:#?}\n영문 댓글: {:#?}",
+                chunks
+                    .iter()
+                    .map(|x| x.translated_text.as_str())
+                    .collect::<Vec<_>>(),
+                translate_comments
+            );
+        }
+
+        Ok(Translated {
+            video,
+            transcript,
+            transcript_outcome,
+            script,
+            comments,
+            chunks,
+            translated_comments: translate_comments,
+        })
+    }
+
+    /// runs the models over a translated video, the second stage of the
+    /// analysis.
+    pub async fn classify(
+        &self,
+        translated: Translated,
+        progress: &dyn Progress,
+    ) -> anyhow::Result<VideoAnalysis> {
+        let Translated {
+            video,
+            transcript,
+            transcript_outcome,
+            script,
+            comments,
+            chunks,
+            translated_comments: translate_comments,
+        } = translated;
+        let video_id = Some(video.id.as_str());
+        let translator = &self.translator;
+        let classifier_hub = &self.classifier_hub;
+        let mut video_signals = VideoSignals::new(&video);
+        let translate_chunks: Vec<String> =
+            chunks.iter().map(|x| x.translated_text.clone()).collect();
+        let translate_script = translate_chunks.join(" ");
+
+        // 1. 질의응답
+        // DistilBERT 모델을 활용하여 유튜브 스크립트의 주제와 결론에 대해 질문하고, 조각마다의 답 중 가장 나은 답을 고른다.
+        progress.start(Stage::QuestionAnswering, video_id);
+        let question_answering_classifier = classifier_hub.question_answering();
+        let question = "What is the theme and conclusion of the video?".to_owned();
+        let translate_answers = question_answering_classifier
+            .predict_chunks(question, translate_chunks.clone())
+            .await?;
+        let answers = translator
+            .translate(
+                &translate_answers
+                    .iter()
+                    .map(|x| x.answer.clone())
+                    .collect::<Vec<_>>(),
+                "EN",
+                "KO",
+            )
+            .await?;
+        if self.verbose {
+            println!("영문 주제와 결론: {translate_answers:#?}");
+            println!("주제와 결론: {answers:#?}");
+        }
+        progress.finish(Stage::QuestionAnswering);
+
+        // 2. 요약
+        // BART 모델을 활용하여 스크립트 조각을 요약하고, 요약들을 다시 요약한다.
+        progress.start(Stage::Summarize, video_id);
+        let summarization_classifier = classifier_hub.summarization();
+        let translate_summarize = summarization_classifier
+            .summarize_chunks(translate_chunks.clone(), MAX_CHUNK_TOKENS)
+            .await?;
+        let summarize = translator
+            .translate(&translate_summarize, "EN", "KO")
+            .await?;
+        if self.verbose {
+            println!("영문 스크립트 요약: {translate_summarize:#?}");
+            println!("스크립트 요약: {summarize:#?}");
+        }
+        progress.finish(Stage::Summarize);
+
+        // 3. 감정 분석
+        // DistilBERT 모델을 활용하여 유튜브 댓글에 대한 이진 감정을 분석한다.
+        progress.start(Stage::Sentiment, video_id);
+        let (sentiments, aspects) = self.sentiments(&translate_comments).await?;
+        progress.finish(Stage::Sentiment);
+        if self.verbose {
+            println!("댓글 감성 분석: {sentiments:#?}");
+            println!("댓글 속성별 감성 분석: {aspects:#?}");
+        }
+        video_signals = video_signals.with_sentiments(&sentiments);
+        video_signals.aspects = aspects;
+        let comment_sentiments = comment_sentiments(&comments, &translate_comments, &sentiments);
+
+        // 4. 키워드 추출
+        // 유튜브 스크립트와 댓글에서 키워드를 추출한다.
+        progress.start(Stage::Keywords, video_id);
+        let keyword_extraction_classifier = classifier_hub.keyword_extraction();
+        let translate_script_keywords = keyword_extraction_classifier
+            .predict(vec![translate_script.clone()])
+            .await?;
+        let script_keywords = translator
+            .translate(
+                &translate_script_keywords
+                    .iter()
+                    .flatten()
+                    .map(|x| x.text.clone())
+                    .collect::<Vec<_>>(),
+                "EN",
+                "KO",
+            )
+            .await?;
+        if self.verbose {
+            println!("영문 스크립트 키워드: {translate_script_keywords:#?}");
+            println!("스크립트 키워드: {script_keywords:#?}");
+        }
+
+        let translate_comments_keywords = keyword_extraction_classifier
+            .predict(translate_comments.clone())
+            .await?;
+        let comments_keywords = translator
+            .translate(
+                &translate_comments_keywords
+                    .iter()
+                    .flatten()
+                    .map(|x| x.text.clone())
+                    .collect::<Vec<_>>(),
+                "EN",
+                "KO",
+            )
+            .await?;
+        if self.verbose {
+            println!("영문 댓글 키워드: {translate_comments_keywords:#?}");
+            println!("댓글 키워드: {comments_keywords:#?}");
+        }
+        progress.finish(Stage::Keywords);
+
+        // 5. 제품 추출
+        // NER 모델과 제품 사전으로 스크립트와 댓글에서 언급된 제품을 찾아 하나의 이름으로 합친다.
+        progress.start(Stage::Products, video_id);
+        let entity_extraction_classifier = classifier_hub.entity_extraction();
+        let entities = entity_extraction_classifier
+            .predict(
+                std::iter::once(translate_script.clone())
+                    .chain(translate_comments.iter().cloned())
+                    .collect(),
+            )
+            .await?;
+        let mut mentions: Vec<Mention> = entities
+            .iter()
+            .flatten()
+            .filter(|x| x.label == "MISC" || x.label == "ORG")
+            .map(|x| Mention {
+                text: x.word.clone(),
+                score: x.score,
+            })
+            .collect();
+        mentions.extend(
+            std::iter::once(&script)
+                .chain(comments.iter())
+                .flat_map(|x| self.product_normalizer.find_mentions(x)),
+        );
+        let products = self.product_normalizer.normalize(&mentions);
+        if self.verbose {
+            println!("언급된 제품: {products:#?}");
+        }
+        progress.finish(Stage::Products);
+
+        // 6. 추천
+        // 언급된 제품을 추천 후보로 삼고, 찾지 못한 경우 스크립트와 댓글 키워드를 후보로 삼는다.
+        video_signals = video_signals.with_products(&products);
+        if products.is_empty() {
+            for keyword in translate_script_keywords
+                .iter()
+                .chain(translate_comments_keywords.iter())
+                .flatten()
+            {
+                video_signals.add_mention(&keyword.text, keyword.score as f64);
+            }
+        }
+
+        Ok(VideoAnalysis {
+            id: video.id.clone(),
+            title: video.snippet.title.clone(),
+            channel_title: video.snippet.channel_title.clone(),
+            published_at: video.snippet.published_at.clone(),
+            view_count: video.statistics.view_count,
+            like_count: video.statistics.like_count,
+            transcript,
+            transcript_outcome,
+            script,
+            translated_script: translate_script,
+            chunks,
+            answers: translate_answers
+                .iter()
+                .zip(answers)
+                .map(|(translate_answer, answer)| QaAnswer {
+                    answer,
+                    translated_answer: translate_answer.answer.clone(),
+                    score: translate_answer.score,
+                })
+                .collect(),
+            summary: summarize,
+            translated_summary: translate_summarize,
+            sentiments: comment_sentiments,
+            aspects: video_signals.aspects.clone(),
+            script_keywords: keyword_scores(&translate_script_keywords, script_keywords),
+            comment_keywords: keyword_scores(&translate_comments_keywords, comments_keywords),
+            products,
+            signals: video_signals,
+        })
+    }
+
+    /// translates the korean comments and classifies their sentiment, one by
+    /// one and per aspect.
+    pub async fn comment_sentiments(
+        &self,
+        comments: Vec<String>,
+    ) -> anyhow::Result<(Vec<CommentSentiment>, Vec<AspectSentiment>)> {
+        let translate_comments = self.translator.translate(&comments, "KO", "EN").await?;
+        let (sentiments, aspects) = self.sentiments(&translate_comments).await?;
+        Ok((
+            comment_sentiments(&comments, &translate_comments, &sentiments),
+            aspects,
+        ))
+    }
+
+    /// sentiment of each english comment, and per aspect of the sentences
+    /// mentioning one.
+    async fn sentiments(
+        &self,
+        translate_comments: &[String],
+    ) -> anyhow::Result<(Vec<Sentiment>, Vec<AspectSentiment>)> {
+        let sentiment_classifier = self.classifier_hub.sentiment();
+        let sentiments = sentiment_classifier
+            .predict(translate_comments.to_vec())
+            .await?;
+
+        // 속성별 감정 분석
+        // 댓글 문장에서 배터리, 가격, 디자인 등의 속성을 찾아 해당 문장의 감정을 속성별로 집계한다.
+        let aspect_mentions = self.aspect_lexicon.mentions(translate_comments);
+        if aspect_mentions.is_empty() {
+            return Ok((sentiments, Vec::new()));
+        }
+        let aspect_sentiments = sentiment_classifier
+            .predict(aspect_mentions.iter().map(|x| x.sentence.clone()).collect())
+            .await?;
+        Ok((
+            sentiments,
+            aspect::summarize(&aspect_mentions, &aspect_sentiments),
+        ))
+    }
+
+    /// stops the classifiers.
+    pub async fn shutdown(self) -> anyhow::Result<()> {
+        self.classifier_hub.shutdown().await
+    }
+}
+
+fn comment_sentiments(
+    comments: &[String],
+    translate_comments: &[String],
+    sentiments: &[Sentiment],
+) -> Vec<CommentSentiment> {
+    comments
+        .iter()
+        .zip(translate_comments)
+        .zip(sentiments)
+        .map(|((comment, translate_comment), sentiment)| {
+            CommentSentiment::new(comment.clone(), translate_comment.clone(), sentiment)
+        })
+        .collect()
+}
+
+fn keyword_scores(translate_keywords: &[Vec<Keyword>], keywords: Vec<String>) -> Vec<KeywordScore> {
+    translate_keywords
+        .iter()
+        .flatten()
+        .zip(keywords)
+        .map(|(translate_keyword, keyword)| KeywordScore {
+            keyword,
+            translated_keyword: translate_keyword.text.clone(),
+            score: translate_keyword.score,
+        })
+        .collect()
+}
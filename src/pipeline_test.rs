@@ -0,0 +1,257 @@
+use crate::classifier::backend::ClassifierBackend;
+use crate::cli::Cli;
+use crate::error::Error;
+use crate::fixture::recording::RecordedRequest;
+use crate::fixture::server::FixtureServer;
+use crate::fixture_test::{copy_fixtures, player_request, replay, youtube_api};
+use crate::job::progress::NoProgress;
+use crate::pipeline::{self, Analyzer, Context};
+use crate::recommend::aspect::AspectLexicon;
+use crate::report::model::{RunOptions, TranscriptOutcome};
+use crate::storage::store::Store;
+use crate::translate::translator::IdentityTranslator;
+use crate::youtube_transcript::parser::{Transcript, TranscriptParser};
+use crate::youtube_transcript::youtube::YoutubeBuilder;
+use clap::Parser;
+
+const QUERY: &str = "갤럭시 S23";
+
+fn options() -> RunOptions {
+    RunOptions {
+        videos: 2,
+        comments: 3,
+        languages: vec!["ko".to_owned()],
+    }
+}
+
+#[tokio::test]
+async fn pipeline_run_test() {
+    let youtube_api = youtube_api(replay().await);
+    let analyzer = Analyzer::new(
+        Box::new(IdentityTranslator),
+        ClassifierBackend::Fake,
+        AspectLexicon::default(),
+        false,
+    );
+    let store = Store::open_in_memory().unwrap();
+    let context = Context {
+        youtube_api: &youtube_api,
+        analyzer: &analyzer,
+        store: Some(&store),
+        progress: &NoProgress,
+    };
+
+    let videos = pipeline::get_videos(&youtube_api, QUERY, 2).await.unwrap();
+    let ids: Vec<String> = videos.into_iter().map(|x| x.id).collect();
+    let mut analyzed = Vec::new();
+    let report = pipeline::run(&context, Some(QUERY), &ids, options(), |analysis| {
+        analyzed.push(analysis.id.clone());
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(analyzed, ids);
+    let (first, second) = (&report.videos[0], &report.videos[1]);
+    assert_eq!(first.transcript.transcripts.len(), 4);
+    assert_eq!(first.transcript_outcome, TranscriptOutcome::Ok);
+    // 짧은 자막은 한 조각으로 번역된다.
+    assert_eq!(first.chunks.len(), 1);
+    assert_eq!(first.chunks[0].translated_text, first.translated_script);
+    // 자막 조각은 문장 부호 없이 이어 붙으므로 앞부분만 본다.
+    assert!(first.summary[0].starts_with("안녕하세요 테크리뷰입니다"));
+    assert_eq!(first.sentiments.len(), 3);
+    assert!(first.products.iter().any(|x| x.name == "Galaxy S23"));
+    // 자막도 댓글도 없는 비디오도 분석된다.
+    assert!(second.transcript.transcripts.is_empty());
+    assert_eq!(second.transcript_outcome, TranscriptOutcome::NoCaptions);
+    assert!(second.sentiments.is_empty());
+    assert_eq!(report.recommendations[0].product, "Galaxy S23");
+
+    // 저장된 자막과 댓글로 다시 실행하면 같은 결과가 나온다.
+    let run_id = report.run_id.unwrap();
+    let rerun = pipeline::rerun(&context, run_id, |_| Ok(())).await.unwrap();
+    assert_ne!(rerun.run_id, Some(run_id));
+    assert_eq!(
+        rerun.videos.iter().map(|x| &x.id).collect::<Vec<_>>(),
+        report.videos.iter().map(|x| &x.id).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        rerun.recommendations[0].product,
+        report.recommendations[0].product
+    );
+    assert_eq!(store.runs(10).unwrap().len(), 2);
+
+    analyzer.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn pipeline_stage_error_test() {
+    let youtube_api = youtube_api(replay().await);
+    let analyzer = Analyzer::new(
+        Box::new(IdentityTranslator),
+        ClassifierBackend::Fake,
+        AspectLexicon::default(),
+        false,
+    );
+    let context = Context {
+        youtube_api: &youtube_api,
+        analyzer: &analyzer,
+        store: None,
+        progress: &NoProgress,
+    };
+
+    // 마지막 단계가 실패하면 앞 단계들도 멈추고 그 오류로 실패한다.
+    let ids = ["Xk3bQ9m2LpA".to_owned(), "Vt7nR1cZ8dE".to_owned()];
+    let mut analyzed = 0;
+    let error = pipeline::run(&context, Some(QUERY), &ids, options(), |_| {
+        analyzed += 1;
+        Err(anyhow::anyhow!("output closed"))
+    })
+    .await
+    .unwrap_err();
+    assert_eq!(error.to_string(), "output closed");
+    assert_eq!(analyzed, 1);
+
+    analyzer.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn pipeline_failure_test() {
+    // 첫 번째 비디오의 플레이어 응답과 시청 페이지, 두 번째 비디오의 댓글을 뺀 픽스처
+    let fixtures = copy_fixtures("pipeline_failure");
+    let watch_page = RecordedRequest::new("GET", "youtube/watch", Some("v=Xk3bQ9m2LpA"), b"");
+    let comments = RecordedRequest::new(
+        "GET",
+        "youtube-api/commentThreads",
+        Some("part=snippet&videoId=Vt7nR1cZ8dE&maxResults=3&pageToken="),
+        b"",
+    );
+    std::fs::remove_file(fixtures.path(&player_request("Xk3bQ9m2LpA"))).unwrap();
+    std::fs::remove_file(fixtures.path(&watch_page)).unwrap();
+    std::fs::remove_file(fixtures.path(&comments)).unwrap();
+    let addr = FixtureServer::replay(fixtures.clone())
+        .spawn("127.0.0.1:0".parse().unwrap())
+        .await
+        .unwrap();
+    let youtube_api = youtube_api(addr).with_concurrency(2);
+    let analyzer = Analyzer::new(
+        Box::new(IdentityTranslator),
+        ClassifierBackend::Fake,
+        AspectLexicon::default(),
+        false,
+    );
+    let context = Context {
+        youtube_api: &youtube_api,
+        analyzer: &analyzer,
+        store: None,
+        progress: &NoProgress,
+    };
+
+    let ids = ["Xk3bQ9m2LpA".to_owned(), "Vt7nR1cZ8dE".to_owned()];
+    let report = pipeline::run(&context, Some(QUERY), &ids, options(), |_| Ok(()))
+        .await
+        .unwrap();
+    // 자막을 가져오지 못한 비디오는 댓글로만 분석한다.
+    assert_eq!(report.videos.len(), 1);
+    let video = &report.videos[0];
+    assert_eq!(video.id, "Xk3bQ9m2LpA");
+    assert!(matches!(
+        video.transcript_outcome,
+        TranscriptOutcome::Unavailable { .. }
+    ));
+    assert!(video.transcript.transcripts.is_empty());
+    assert_eq!(video.sentiments.len(), 3);
+    // 댓글을 가져오지 못한 비디오는 빼고 나머지를 분석한다.
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].id, "Vt7nR1cZ8dE");
+    assert!(!report.failures[0].error.is_empty());
+
+    analyzer.shutdown().await.unwrap();
+    std::fs::remove_dir_all(fixtures.dir()).unwrap();
+}
+
+#[tokio::test]
+async fn pipeline_transcript_outcome_test() {
+    let builder = YoutubeBuilder::default();
+    let youtube = builder.build();
+    let outcome = |page: &str| {
+        let transcript = youtube
+            .caption_tracks_from_text(page)
+            .map_err(Error::from)
+            .map(|captions| {
+                assert!(captions.is_empty());
+                Transcript::default()
+            });
+        pipeline::transcript_outcome(transcript).1
+    };
+
+    // 자막이 하나도 없는 비디오는 자막 목록이 없다.
+    let playable =
+        r#"var ytInitialPlayerResponse = {"playabilityStatus":{"status":"OK"},"videoDetails":{}};"#;
+    assert_eq!(outcome(playable), TranscriptOutcome::NoCaptions);
+    let private = r#"{"playabilityStatus":{"status":"LOGIN_REQUIRED","reason":"private"}}"#;
+    assert!(matches!(
+        outcome(private),
+        TranscriptOutcome::Unavailable { reason } if reason.contains("LOGIN_REQUIRED")
+    ));
+    let consent = "<html><form action=\"https://consent.youtube.com/save\"></form></html>";
+    assert!(matches!(
+        outcome(consent),
+        TranscriptOutcome::Unavailable { .. }
+    ));
+
+    // 자막 xml 을 읽지 못한 경우
+    let malformed = TranscriptParser::parse("<transcript><text>").map_err(Error::from);
+    assert!(matches!(
+        pipeline::transcript_outcome(malformed).1,
+        TranscriptOutcome::ParseFailed { .. }
+    ));
+}
+
+/// runs `recommend` as from the command line, against the fixtures.
+#[tokio::test]
+async fn pipeline_main_test() {
+    let addr = replay().await;
+    // 다른 테스트와 함께 실행되므로 환경 변수 대신 인자로 주소를 준다.
+    let youtube_api_url = format!("http://{}/youtube-api", addr);
+    let youtube_url = format!("http://{}/youtube", addr);
+    let output = std::env::temp_dir().join(format!(
+        "youtube_recommend_report_{}.json",
+        std::process::id()
+    ));
+
+    let cli = Cli::parse_from([
+        "youtube_recommend",
+        "recommend",
+        QUERY,
+        "--videos",
+        "2",
+        "--comments",
+        "3",
+        "--classifier",
+        "fake",
+        "--translator",
+        "identity",
+        "--no-cache",
+        "--no-store",
+        "--format",
+        "json",
+        "--output",
+        output.to_str().unwrap(),
+        "--youtube-api-key",
+        "test-key",
+        "--youtube-api-url",
+        &youtube_api_url,
+        "--youtube-url",
+        &youtube_url,
+    ]);
+    crate::run(cli).await.unwrap();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    std::fs::remove_file(&output).unwrap();
+    assert_eq!(report["query"], QUERY);
+    assert_eq!(report["videos"].as_array().unwrap().len(), 2);
+    assert_eq!(report["recommendations"][0]["product"], "Galaxy S23");
+}
@@ -0,0 +1,69 @@
+use reqwest::Url;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+// YouTube Data API 는 하루 할당량이 있으므로 몰아서 보내지 않는다.
+pub const INTERVAL_YOUTUBE_API: Duration = Duration::from_millis(100);
+// 시청 페이지와 자막을 너무 빨리 가져오면 429 로 막힌다.
+pub const INTERVAL_YOUTUBE: Duration = Duration::from_millis(250);
+
+/// Spaces out the requests sent to each host. A host without an interval is
+/// not limited. Clones share the schedule, so concurrent fetches through
+/// clones still respect the interval.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    /// least time between two requests, per host
+    intervals: HashMap<String, Duration>,
+    /// time the next request to a host may be sent at
+    next: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl Default for RateLimiter {
+    /// limits the YouTube Data API and the YouTube site.
+    fn default() -> Self {
+        Self::unlimited()
+            .with_host("www.googleapis.com", INTERVAL_YOUTUBE_API)
+            .with_host("www.youtube.com", INTERVAL_YOUTUBE)
+    }
+}
+
+impl RateLimiter {
+    /// creates [`RateLimiter`] limiting no host.
+    pub fn unlimited() -> Self {
+        Self {
+            intervals: HashMap::new(),
+            next: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// sends the requests to `host` at least `interval` apart. Ex: "www.youtube.com"
+    pub fn with_host(mut self, host: impl Into<String>, interval: Duration) -> Self {
+        self.intervals.insert(host.into(), interval);
+        self
+    }
+
+    /// waits until a request to the host of `url` may be sent, and books the
+    /// time for it.
+    pub async fn wait(&self, url: &str) {
+        let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|x| x.host_str().map(str::to_owned))
+        else {
+            return;
+        };
+        let Some(interval) = self.intervals.get(&host) else {
+            return;
+        };
+
+        let at = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+            let at = next.get(&host).map_or(now, |&x| x.max(now));
+            next.insert(host, at + *interval);
+            at
+        };
+        tokio::time::sleep_until(at).await;
+    }
+}
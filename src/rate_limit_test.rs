@@ -0,0 +1,28 @@
+use crate::rate_limit::RateLimiter;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const INTERVAL: Duration = Duration::from_millis(50);
+
+#[tokio::test]
+async fn rate_limit_test() {
+    let rate_limiter = RateLimiter::unlimited().with_host("example.com", INTERVAL);
+    let started = Instant::now();
+
+    // 복제본끼리도 간격을 나눠 쓴다.
+    let clone = rate_limiter.clone();
+    tokio::join!(
+        rate_limiter.wait("https://example.com/a"),
+        clone.wait("https://example.com/b"),
+        rate_limiter.wait("https://example.com/c"),
+    );
+    assert!(started.elapsed() >= INTERVAL * 2);
+
+    // 제한이 없는 호스트는 기다리지 않는다.
+    let started = Instant::now();
+    for _ in 0..10 {
+        rate_limiter.wait("http://127.0.0.1:8090/youtube").await;
+        rate_limiter.wait("not a url").await;
+    }
+    assert!(started.elapsed() < INTERVAL);
+}
@@ -0,0 +1,265 @@
+use rust_bert::pipelines::sentiment::{Sentiment, SentimentPolarity};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Aspects looked for by default, with the terms that point at them.
+const DEFAULT_ASPECTS: &[(&str, &[&str])] = &[
+    (
+        "battery",
+        &["battery", "charge", "charging", "배터리", "충전"],
+    ),
+    (
+        "price",
+        &[
+            "price",
+            "cost",
+            "expensive",
+            "cheap",
+            "value",
+            "가격",
+            "비싸",
+            "가성비",
+        ],
+    ),
+    (
+        "design",
+        &[
+            "design",
+            "color",
+            "colour",
+            "weight",
+            "thin",
+            "디자인",
+            "색상",
+            "무게",
+        ],
+    ),
+    (
+        "camera",
+        &["camera", "photo", "picture", "zoom", "카메라", "사진"],
+    ),
+    (
+        "performance",
+        &[
+            "performance",
+            "speed",
+            "fast",
+            "slow",
+            "lag",
+            "chip",
+            "heat",
+            "성능",
+            "속도",
+            "발열",
+        ],
+    ),
+    (
+        "display",
+        &["display", "screen", "brightness", "화면", "디스플레이"],
+    ),
+    ("sound", &["sound", "speaker", "audio", "음질", "스피커"]),
+];
+
+/// Aspect with the terms that point at it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Aspect {
+    pub name: String,
+    pub terms: Vec<String>,
+}
+
+/// Sentence of a text that talks about an aspect.
+#[derive(Debug, Clone)]
+pub struct AspectMention {
+    pub aspect: String,
+    pub sentence: String,
+}
+
+/// Polarity distribution of an aspect.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AspectSentiment {
+    pub aspect: String,
+    pub positive: usize,
+    pub negative: usize,
+    /// sum of the signed sentiment scores
+    pub score: f64,
+}
+
+impl AspectSentiment {
+    pub fn mentions(&self) -> usize {
+        self.positive + self.negative
+    }
+
+    /// mean of the signed sentiment scores, between -1 and 1
+    pub fn polarity(&self) -> f64 {
+        match self.mentions() {
+            0 => 0.0,
+            mentions => self.score / mentions as f64,
+        }
+    }
+}
+
+/// Configurable list of [`Aspect`]s used to find aspect mentions in comments.
+#[derive(Debug, Clone)]
+pub struct AspectLexicon {
+    aspects: Vec<Aspect>,
+}
+
+impl Default for AspectLexicon {
+    fn default() -> Self {
+        Self {
+            aspects: DEFAULT_ASPECTS
+                .iter()
+                .map(|(name, terms)| Aspect {
+                    name: name.to_string(),
+                    terms: terms.iter().map(|x| x.to_string()).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl AspectLexicon {
+    /// creates [`AspectLexicon`], lowercasing the terms as [`Self::detect`]
+    /// lowercases the text.
+    pub fn new(aspects: Vec<Aspect>) -> Self {
+        Self {
+            aspects: aspects
+                .into_iter()
+                .map(|aspect| Aspect {
+                    terms: aspect.terms.iter().map(|x| x.to_lowercase()).collect(),
+                    ..aspect
+                })
+                .collect(),
+        }
+    }
+
+    /// reads a json list of [`Aspect`]s. Ex: `[{"name": "battery", "terms": ["battery", "배터리"]}]`
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self::new(serde_json::from_str(&text)?))
+    }
+
+    /// reads the file of `ASPECT_LEXICON`, the default aspects if it is not set.
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var("ASPECT_LEXICON") {
+            Ok(path) => Self::from_file(path),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// adds the terms of an aspect, creating it if needed.
+    pub fn with_aspect(mut self, name: &str, terms: &[&str]) -> Self {
+        let terms = terms.iter().map(|x| x.to_lowercase());
+        match self.aspects.iter_mut().find(|x| x.name == name) {
+            Some(aspect) => aspect.terms.extend(terms),
+            None => self.aspects.push(Aspect {
+                name: name.to_owned(),
+                terms: terms.collect(),
+            }),
+        }
+        self
+    }
+
+    /// aspects a text talks about.
+    pub fn detect(&self, text: &str) -> Vec<&str> {
+        let text = text.to_lowercase();
+        let words: Vec<&str> = text
+            .split(|x: char| !x.is_alphanumeric())
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        self.aspects
+            .iter()
+            .filter(|aspect| {
+                aspect.terms.iter().any(|term| {
+                    if term.is_ascii() {
+                        // "prices" should match "price", "costume" shouldn't match "cost"
+                        words.iter().any(|word| inflects(word, term))
+                    } else {
+                        text.contains(term.as_str())
+                    }
+                })
+            })
+            .map(|aspect| aspect.name.as_str())
+            .collect()
+    }
+
+    /// splits the texts into sentences and keeps the ones mentioning an aspect,
+    /// once per aspect.
+    pub fn mentions(&self, texts: &[String]) -> Vec<AspectMention> {
+        texts
+            .iter()
+            .flat_map(|text| text.split(['.', '!', '?', '\n']))
+            .map(str::trim)
+            .filter(|sentence| !sentence.is_empty())
+            .flat_map(|sentence| {
+                self.detect(sentence)
+                    .into_iter()
+                    .map(move |aspect| AspectMention {
+                        aspect: aspect.to_owned(),
+                        sentence: sentence.to_owned(),
+                    })
+            })
+            .collect()
+    }
+}
+
+/// whether `word` is `term` or one of its regular english inflections.
+/// Ex: "prices", "charging", "batteries" and "thinner" for "price", "charge",
+/// "battery" and "thin", but not "think" for "thin"
+pub(crate) fn inflects(word: &str, term: &str) -> bool {
+    const SUFFIXES: [&str; 8] = ["", "s", "es", "d", "ed", "ing", "er", "est"];
+    let stems = [
+        Some(term.to_owned()),
+        term.strip_suffix('e').map(str::to_owned),
+        term.strip_suffix('y').map(|x| format!("{}i", x)),
+        term.chars().last().map(|x| format!("{}{}", term, x)),
+    ];
+    stems.iter().flatten().any(|stem| {
+        word.strip_prefix(stem.as_str())
+            .is_some_and(|rest| SUFFIXES.contains(&rest))
+    })
+}
+
+/// aggregates the sentiments of the mentions, `sentiments[i]` being the
+/// sentiment of `mentions[i].sentence`. Most mentioned aspects come first.
+pub fn summarize(mentions: &[AspectMention], sentiments: &[Sentiment]) -> Vec<AspectSentiment> {
+    let mut summary: Vec<AspectSentiment> = Vec::new();
+
+    for (mention, sentiment) in mentions.iter().zip(sentiments) {
+        let (positive, negative, score) = match sentiment.polarity {
+            SentimentPolarity::Positive => (1, 0, sentiment.score),
+            SentimentPolarity::Negative => (0, 1, -sentiment.score),
+        };
+        merge(
+            &mut summary,
+            &[AspectSentiment {
+                aspect: mention.aspect.clone(),
+                positive,
+                negative,
+                score,
+            }],
+        );
+    }
+
+    summary
+}
+
+/// adds the distributions of `from` to the ones of the same aspect in `into`.
+pub fn merge(into: &mut Vec<AspectSentiment>, from: &[AspectSentiment]) {
+    for aspect in from {
+        match into.iter_mut().find(|x| x.aspect == aspect.aspect) {
+            Some(x) => {
+                x.positive += aspect.positive;
+                x.negative += aspect.negative;
+                x.score += aspect.score;
+            }
+            None => into.push(aspect.clone()),
+        }
+    }
+    into.sort_by(|a, b| {
+        b.mentions()
+            .cmp(&a.mentions())
+            .then_with(|| a.aspect.cmp(&b.aspect))
+    });
+}
@@ -0,0 +1,3 @@
+pub mod aspect;
+pub mod product;
+pub mod recommender;
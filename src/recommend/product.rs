@@ -0,0 +1,305 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Korean and alternative spellings mapped to the token used for matching.
+const DEFAULT_ALIASES: &[(&str, &str)] = &[
+    ("갤럭시", "galaxy"),
+    ("아이폰", "iphone"),
+    ("아이패드", "ipad"),
+    ("맥북", "macbook"),
+    ("에어팟", "airpods"),
+    ("버즈", "buds"),
+    ("픽셀", "pixel"),
+    ("울트라", "ultra"),
+    ("프로", "pro"),
+    ("플러스", "plus"),
+    ("맥스", "max"),
+    ("미니", "mini"),
+    ("에어", "air"),
+    ("삼성", "samsung"),
+    ("애플", "apple"),
+    ("구글", "google"),
+    ("소니", "sony"),
+    ("엘지", "lg"),
+];
+
+/// Product lines, with the way they are written in canonical names.
+const DEFAULT_SERIES: &[(&str, &str)] = &[
+    ("galaxy", "Galaxy"),
+    ("iphone", "iPhone"),
+    ("ipad", "iPad"),
+    ("macbook", "MacBook"),
+    ("airpods", "AirPods"),
+    ("buds", "Buds"),
+    ("pixel", "Pixel"),
+];
+
+/// Brands and variant names left out of the key, so that "Samsung Galaxy S23"
+/// and "S23 Ultra" end up with "Galaxy S23".
+const DEFAULT_IGNORED: &[&str] = &[
+    "samsung", "apple", "google", "sony", "lg", "ultra", "pro", "plus", "max", "mini", "air",
+    "lite", "fe",
+];
+
+/// Product mention found in a text, either by the NER model or by
+/// [`ProductNormalizer::find_mentions`].
+#[derive(Debug, Clone)]
+pub struct Mention {
+    pub text: String,
+    /// confidence of the mention, `1.0` for lexicon matches
+    pub score: f64,
+}
+
+/// Canonical product merged from several mentions.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductEntity {
+    /// canonical name. Ex: "Galaxy S23"
+    pub name: String,
+    /// distinct spellings merged into this product. Ex: ["갤럭시 S23", "S23 Ultra"]
+    pub mentions: Vec<String>,
+    pub count: usize,
+    /// sum of the mention scores
+    pub score: f64,
+}
+
+/// Tokens of a mention after alias replacement.
+struct Normalized {
+    series: Option<String>,
+    code: Option<String>,
+    rest: Vec<String>,
+}
+
+/// Merges product mentions written in different ways into canonical entities.
+pub struct ProductNormalizer {
+    aliases: HashMap<String, String>,
+    series: HashMap<String, String>,
+    ignored: Vec<String>,
+}
+
+impl Default for ProductNormalizer {
+    fn default() -> Self {
+        Self {
+            aliases: DEFAULT_ALIASES
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect(),
+            series: DEFAULT_SERIES
+                .iter()
+                .map(|(token, name)| (token.to_string(), name.to_string()))
+                .collect(),
+            ignored: DEFAULT_IGNORED.iter().map(|x| x.to_string()).collect(),
+        }
+    }
+}
+
+impl ProductNormalizer {
+    /// maps `from` to `to` before matching. Ex: `("겔럭시", "galaxy")`
+    pub fn with_alias(mut self, from: &str, to: &str) -> Self {
+        self.aliases.insert(from.to_lowercase(), to.to_lowercase());
+        self
+    }
+
+    /// adds a product line, `name` being how it is written in canonical names.
+    pub fn with_series(mut self, name: &str) -> Self {
+        self.series.insert(name.to_lowercase(), name.to_owned());
+        self
+    }
+
+    /// finds "<series> <model code>" mentions such as "갤럭시 S23" in a raw text.
+    pub fn find_mentions(&self, text: &str) -> Vec<Mention> {
+        let words = split_words(text);
+        let mut mentions = Vec::new();
+
+        for (i, word) in words.iter().enumerate() {
+            if !self.series.contains_key(&self.token(word)) {
+                continue;
+            }
+            if let Some(next) = words.get(i + 1).filter(|x| is_code(x)) {
+                mentions.push(Mention {
+                    text: format!("{} {}", word, next),
+                    score: 1.0,
+                });
+            }
+        }
+
+        mentions
+    }
+
+    /// merges the mentions into products, most mentioned first. Mentions that
+    /// carry neither a known product line nor a model code (bare brand names,
+    /// people, places) are dropped.
+    pub fn normalize(&self, mentions: &[Mention]) -> Vec<ProductEntity> {
+        let mut products: Vec<(Option<String>, Option<String>, ProductEntity)> = Vec::new();
+        let mut code_only = Vec::new();
+
+        for mention in mentions {
+            let normalized = self.normalize_one(&mention.text);
+            match (&normalized.series, &normalized.code) {
+                (None, None) => continue,
+                (None, Some(_)) => {
+                    code_only.push((mention, normalized));
+                    continue;
+                }
+                _ => {}
+            }
+
+            let position = products.iter().position(|(series, code, product)| {
+                match (&normalized.code, code) {
+                    (Some(_), _) => *series == normalized.series && *code == normalized.code,
+                    (None, None) => product.name == self.name(&normalized, &mention.text),
+                    (None, Some(_)) => false,
+                }
+            });
+            let index = position.unwrap_or_else(|| {
+                products.push((
+                    normalized.series.clone(),
+                    normalized.code.clone(),
+                    self.entity(&normalized, &mention.text),
+                ));
+                products.len() - 1
+            });
+            add(&mut products[index].2, mention);
+        }
+
+        // "S23 Ultra" joins "Galaxy S23" when no other product line has a S23
+        for (mention, normalized) in code_only {
+            let mut matches = products
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, code, _))| *code == normalized.code)
+                .map(|(i, _)| i);
+            let index = match (matches.next(), matches.next()) {
+                (Some(i), None) => i,
+                _ => match products
+                    .iter()
+                    .position(|(series, code, _)| series.is_none() && *code == normalized.code)
+                {
+                    Some(i) => i,
+                    None => {
+                        products.push((
+                            None,
+                            normalized.code.clone(),
+                            self.entity(&normalized, &mention.text),
+                        ));
+                        products.len() - 1
+                    }
+                },
+            };
+            add(&mut products[index].2, mention);
+        }
+
+        let mut products: Vec<ProductEntity> = products.into_iter().map(|x| x.2).collect();
+        products.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| b.score.total_cmp(&a.score))
+        });
+        products
+    }
+
+    fn token(&self, word: &str) -> String {
+        let word = word.to_lowercase();
+        self.aliases.get(&word).cloned().unwrap_or(word)
+    }
+
+    fn normalize_one(&self, text: &str) -> Normalized {
+        let mut normalized = Normalized {
+            series: None,
+            code: None,
+            rest: Vec::new(),
+        };
+
+        for word in split_words(text) {
+            let token = self.token(&word);
+            if normalized.series.is_none() && self.series.contains_key(&token) {
+                normalized.series = Some(token);
+            } else if normalized.code.is_none() && is_code(&token) {
+                normalized.code = Some(token);
+            } else if !self.ignored.contains(&token) {
+                normalized.rest.push(token);
+            }
+        }
+
+        normalized
+    }
+
+    fn name(&self, normalized: &Normalized, text: &str) -> String {
+        let series = normalized.series.as_ref().map(|x| self.series[x].clone());
+        let code = normalized.code.as_deref().map(capitalize);
+
+        match (series, code) {
+            (Some(series), Some(code)) => format!("{} {}", series, code),
+            (None, Some(code)) => code,
+            (Some(series), None) => std::iter::once(series)
+                .chain(normalized.rest.iter().map(|x| capitalize(x)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            (None, None) => text.trim().to_owned(),
+        }
+    }
+
+    fn entity(&self, normalized: &Normalized, text: &str) -> ProductEntity {
+        ProductEntity {
+            name: self.name(normalized, text),
+            mentions: Vec::new(),
+            count: 0,
+            score: 0.0,
+        }
+    }
+}
+
+fn add(product: &mut ProductEntity, mention: &Mention) {
+    let text = mention.text.trim().to_owned();
+    if !product.mentions.contains(&text) {
+        product.mentions.push(text);
+    }
+    product.count += 1;
+    product.score += mention.score;
+}
+
+/// a model code has at least one digit. Ex: "S23", "15", "WH-1000XM5"
+fn is_code(word: &str) -> bool {
+    word.chars().any(|x| x.is_ascii_digit())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn is_hangul(c: char) -> bool {
+    ('\u{AC00}'..='\u{D7A3}').contains(&c) || ('\u{3131}'..='\u{318E}').contains(&c)
+}
+
+/// splits on whitespace and punctuation (keeping '-' inside words) and between
+/// hangul and latin characters, so that "갤럭시S23" gives ["갤럭시", "S23"].
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for c in text.chars() {
+        let boundary = word
+            .chars()
+            .last()
+            .is_some_and(|last| is_hangul(last) != is_hangul(c));
+        if !(c.is_alphanumeric() || c == '-') || boundary {
+            let trimmed = word.trim_matches('-');
+            if !trimmed.is_empty() {
+                words.push(trimmed.to_owned());
+            }
+            word.clear();
+        }
+        if c.is_alphanumeric() || c == '-' {
+            word.push(c);
+        }
+    }
+    let trimmed = word.trim_matches('-');
+    if !trimmed.is_empty() {
+        words.push(trimmed.to_owned());
+    }
+
+    words
+}
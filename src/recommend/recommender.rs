@@ -0,0 +1,233 @@
+use crate::recommend::aspect::{self, AspectSentiment};
+use crate::recommend::product::ProductEntity;
+use crate::youtube_api::model::Video;
+use rust_bert::pipelines::sentiment::{Sentiment, SentimentPolarity};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Signals collected for a single video, the input of [`Recommender::rank`].
+#[derive(Debug, Clone, Default)]
+pub struct VideoSignals {
+    pub video_id: String,
+    pub title: String,
+    pub channel_title: String,
+    pub view_count: Option<u64>,
+    pub like_count: Option<u64>,
+    pub positive_comments: usize,
+    pub negative_comments: usize,
+    /// mean of the signed comment sentiment scores, between -1 and 1
+    pub sentiment: f64,
+    /// products the video talks about, with how strongly it talks about them
+    pub mentions: HashMap<String, f64>,
+    /// comment sentiment per aspect
+    pub aspects: Vec<AspectSentiment>,
+}
+
+impl VideoSignals {
+    /// creates [`VideoSignals`] from the video metadata.
+    pub fn new(video: &Video) -> Self {
+        Self {
+            video_id: video.id.clone(),
+            title: video.snippet.title.clone(),
+            channel_title: video.snippet.channel_title.clone(),
+            view_count: video.statistics.view_count,
+            like_count: video.statistics.like_count,
+            ..Default::default()
+        }
+    }
+
+    /// records the comment sentiments of the video.
+    pub fn with_sentiments(mut self, sentiments: &[Sentiment]) -> Self {
+        let mut total = 0.0;
+
+        for sentiment in sentiments {
+            match sentiment.polarity {
+                SentimentPolarity::Positive => {
+                    self.positive_comments += 1;
+                    total += sentiment.score;
+                }
+                SentimentPolarity::Negative => {
+                    self.negative_comments += 1;
+                    total -= sentiment.score;
+                }
+            }
+        }
+
+        if !sentiments.is_empty() {
+            self.sentiment = total / sentiments.len() as f64;
+        }
+        self
+    }
+
+    /// adds `weight` to the mentions of `product`. Product names are compared
+    /// case-insensitively, the first spelling seen is kept.
+    pub fn add_mention(&mut self, product: &str, weight: f64) {
+        let product = product.trim();
+        if product.is_empty() {
+            return;
+        }
+
+        let key = self
+            .mentions
+            .keys()
+            .find(|x| x.to_lowercase() == product.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| product.to_owned());
+        *self.mentions.entry(key).or_default() += weight;
+    }
+
+    /// adds every product of the video, weighted by its mention score.
+    pub fn with_products(mut self, products: &[ProductEntity]) -> Self {
+        for product in products {
+            self.add_mention(&product.name, product.score);
+        }
+        self
+    }
+}
+
+/// Weights of the video signals, relative to each other.
+#[derive(Debug, Clone, Serialize)]
+pub struct Weights {
+    pub sentiment: f64,
+    pub popularity: f64,
+    pub engagement: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            sentiment: 0.5,
+            popularity: 0.3,
+            engagement: 0.2,
+        }
+    }
+}
+
+/// How a single video contributed to the score of a product.
+#[derive(Debug, Clone, Serialize)]
+pub struct Evidence {
+    pub video_id: String,
+    pub title: String,
+    pub channel_title: String,
+    pub view_count: Option<u64>,
+    pub positive_comments: usize,
+    pub negative_comments: usize,
+    pub sentiment: f64,
+    /// mentions of the product in the video
+    pub relevance: f64,
+    /// quality of the video between 0 and 1, see [`Recommender::quality`]
+    pub quality: f64,
+    /// `relevance * quality`
+    pub contribution: f64,
+}
+
+/// Product with its score and the videos behind it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub product: String,
+    pub score: f64,
+    /// comment sentiment per aspect over every video mentioning the product
+    pub aspects: Vec<AspectSentiment>,
+    /// videos sorted by their contribution, highest first
+    pub evidence: Vec<Evidence>,
+}
+
+/// Ranks products by aggregating the signals of the videos mentioning them.
+#[derive(Debug, Clone, Default)]
+pub struct Recommender {
+    weights: Weights,
+}
+
+impl Recommender {
+    pub fn new(weights: Weights) -> Self {
+        Self { weights }
+    }
+
+    /// ranks every mentioned product, highest score first. Product names are
+    /// compared case-insensitively.
+    pub fn rank(&self, videos: &[VideoSignals]) -> Vec<Recommendation> {
+        let max_views = videos.iter().filter_map(|x| x.view_count).max();
+        let max_like_ratio = videos.iter().filter_map(like_ratio).fold(0.0_f64, f64::max);
+
+        let mut recommendations: HashMap<String, Recommendation> = HashMap::new();
+
+        for video in videos {
+            let quality = self.quality(video, max_views, max_like_ratio);
+
+            for (product, &relevance) in &video.mentions {
+                let recommendation = recommendations
+                    .entry(product.to_lowercase())
+                    .or_insert_with(|| Recommendation {
+                        product: product.clone(),
+                        score: 0.0,
+                        aspects: Vec::new(),
+                        evidence: Vec::new(),
+                    });
+                recommendation.score += relevance * quality;
+                aspect::merge(&mut recommendation.aspects, &video.aspects);
+                recommendation.evidence.push(Evidence {
+                    video_id: video.video_id.clone(),
+                    title: video.title.clone(),
+                    channel_title: video.channel_title.clone(),
+                    view_count: video.view_count,
+                    positive_comments: video.positive_comments,
+                    negative_comments: video.negative_comments,
+                    sentiment: video.sentiment,
+                    relevance,
+                    quality,
+                    contribution: relevance * quality,
+                });
+            }
+        }
+
+        let mut recommendations: Vec<Recommendation> = recommendations.into_values().collect();
+        for recommendation in &mut recommendations {
+            recommendation
+                .evidence
+                .sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+        }
+        recommendations.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.product.cmp(&b.product))
+        });
+        recommendations
+    }
+
+    /// weighted mean of the comment sentiment, the view count relative to the
+    /// most viewed video (log scale) and the like ratio relative to the best one.
+    pub fn quality(
+        &self,
+        video: &VideoSignals,
+        max_views: Option<u64>,
+        max_like_ratio: f64,
+    ) -> f64 {
+        let sentiment = (video.sentiment + 1.0) / 2.0;
+        let popularity = match (video.view_count, max_views) {
+            (Some(views), Some(max_views)) if max_views > 0 => {
+                (views as f64).ln_1p() / (max_views as f64).ln_1p()
+            }
+            _ => 0.0,
+        };
+        let engagement = match like_ratio(video) {
+            Some(ratio) if max_like_ratio > 0.0 => ratio / max_like_ratio,
+            _ => 0.0,
+        };
+
+        let total = self.weights.sentiment + self.weights.popularity + self.weights.engagement;
+        if total <= 0.0 {
+            return 0.0;
+        }
+        (self.weights.sentiment * sentiment
+            + self.weights.popularity * popularity
+            + self.weights.engagement * engagement)
+            / total
+    }
+}
+
+fn like_ratio(video: &VideoSignals) -> Option<f64> {
+    match (video.like_count, video.view_count) {
+        (Some(likes), Some(views)) if views > 0 => Some(likes as f64 / views as f64),
+        _ => None,
+    }
+}
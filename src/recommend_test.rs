@@ -0,0 +1,125 @@
+use crate::recommend::aspect::AspectLexicon;
+use crate::recommend::product::{Mention, ProductNormalizer};
+use crate::recommend::recommender::{Recommender, VideoSignals, Weights};
+
+fn video(id: &str, view_count: u64, sentiment: f64, mentions: &[(&str, f64)]) -> VideoSignals {
+    let mut video = VideoSignals {
+        video_id: id.to_owned(),
+        title: id.to_owned(),
+        view_count: Some(view_count),
+        like_count: Some(view_count / 10),
+        sentiment,
+        ..Default::default()
+    };
+    for (product, weight) in mentions {
+        video.add_mention(product, *weight);
+    }
+    video
+}
+
+#[test]
+fn recommender_rank_test() {
+    let videos = vec![
+        video(
+            "a",
+            1_000_000,
+            0.8,
+            &[("Galaxy S23", 1.0), ("iPhone 15", 0.5)],
+        ),
+        video("b", 10_000, -0.6, &[("iPhone 15", 1.0)]),
+        video("c", 500_000, 0.4, &[("galaxy s23", 0.5)]),
+    ];
+
+    let recommendations = Recommender::default().rank(&videos);
+    println!("recommendations: {recommendations:#?}");
+
+    assert_eq!(recommendations.len(), 2);
+    assert_eq!(recommendations[0].product, "Galaxy S23");
+    assert_eq!(recommendations[0].evidence.len(), 2);
+    assert_eq!(recommendations[0].evidence[0].video_id, "a");
+    assert!(recommendations[0].score > recommendations[1].score);
+}
+
+#[test]
+fn recommender_quality_test() {
+    let recommender = Recommender::new(Weights {
+        sentiment: 1.0,
+        popularity: 0.0,
+        engagement: 0.0,
+    });
+
+    let positive = video("a", 100, 1.0, &[]);
+    let negative = video("b", 100, -1.0, &[]);
+
+    assert_eq!(recommender.quality(&positive, Some(100), 0.1), 1.0);
+    assert_eq!(recommender.quality(&negative, Some(100), 0.1), 0.0);
+}
+
+#[test]
+fn product_normalizer_test() {
+    let normalizer = ProductNormalizer::default();
+    let mut mentions: Vec<Mention> = ["Galaxy S23", "S23 Ultra", "Samsung", "iPhone 15 Pro"]
+        .into_iter()
+        .map(|x| Mention {
+            text: x.to_owned(),
+            score: 0.9,
+        })
+        .collect();
+    mentions.extend(normalizer.find_mentions("갤럭시 S23 카메라가 아이폰15보다 좋네요"));
+
+    let products = normalizer.normalize(&mentions);
+    println!("products: {products:#?}");
+
+    assert_eq!(products.len(), 2);
+    assert_eq!(products[0].name, "Galaxy S23");
+    assert_eq!(products[0].count, 3);
+    assert_eq!(
+        products[0].mentions,
+        vec!["Galaxy S23", "갤럭시 S23", "S23 Ultra"]
+    );
+    assert_eq!(products[1].name, "iPhone 15");
+    assert_eq!(products[1].count, 2);
+}
+
+#[test]
+fn aspect_lexicon_test() {
+    let lexicon = AspectLexicon::default().with_aspect("durability", &["scratch", "내구성"]);
+    let texts = vec![
+        "The battery lasts two days! But the price is too high.".to_owned(),
+        "Great camera and photos, no scratches after a month".to_owned(),
+        "I wore a costume".to_owned(),
+        // 비슷하게 생긴 다른 단어는 측면이 아니다.
+        "I think there are better things to fasten".to_owned(),
+    ];
+
+    let mentions = lexicon.mentions(&texts);
+    let aspects: Vec<&str> = mentions.iter().map(|x| x.aspect.as_str()).collect();
+    println!("mentions: {mentions:#?}");
+
+    assert_eq!(aspects, vec!["battery", "price", "camera", "durability"]);
+    assert_eq!(mentions[1].sentence, "But the price is too high");
+    assert_eq!(
+        lexicon.detect("The thinner one charges faster than older batteries"),
+        ["battery", "design", "performance"]
+    );
+    assert!(lexicon.detect("I think the things are fine").is_empty());
+}
+
+#[test]
+fn aspect_lexicon_file_test() {
+    let path = std::env::temp_dir().join(format!("aspects_{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"[{"name": "display", "terms": ["OLED", "Screen"]}, {"name": "battery", "terms": ["배터리"]}]"#,
+    )
+    .unwrap();
+    let lexicon = AspectLexicon::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // 사전의 대소문자와 상관없이 찾는다.
+    assert_eq!(
+        lexicon.detect("The oled screens look great, 배터리도 좋다"),
+        ["display", "battery"]
+    );
+    assert!(lexicon.detect("The camera is fine").is_empty());
+}
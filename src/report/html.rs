@@ -0,0 +1,293 @@
+use crate::recommend::recommender::Recommendation;
+use crate::report::model::{self, RunReport, VideoAnalysis};
+use crate::youtube_transcript::parser::TranscriptCore;
+
+/// keywords shown per video
+pub(crate) const MAX_KEYWORDS: usize = 10;
+/// timestamp links shown per product and video
+pub(crate) const MAX_TIMESTAMPS: usize = 5;
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:960px;margin:2em auto;padding:0 1em;color:#222}\
+table{border-collapse:collapse;width:100%}th,td{border-bottom:1px solid #ddd;padding:.4em;text-align:left}\
+section{margin-bottom:2.5em}.keywords span{display:inline-block;background:#eef;border-radius:4px;padding:.1em .5em;margin:.15em}\
+.muted{color:#777}";
+
+const BAR_WIDTH: usize = 260;
+const BAR_HEIGHT: usize = 18;
+const LABEL_WIDTH: usize = 120;
+const POSITIVE_COLOR: &str = "#3c9a5f";
+const NEGATIVE_COLOR: &str = "#d0504f";
+
+/// Row of a sentiment bar chart.
+struct Bar<'a> {
+    pub label: &'a str,
+    pub positive: usize,
+    pub negative: usize,
+}
+
+/// renders the report as a self-contained HTML page: no script, no external
+/// stylesheet and charts drawn as inline SVG.
+pub fn render(report: &RunReport) -> String {
+    let title = match &report.query {
+        Some(query) => format!("추천 리포트: {}", query),
+        None => "추천 리포트".to_owned(),
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ko\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape(&title)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str(&format!("<h1>{}</h1>\n", escape(&title)));
+    html.push_str(&format!(
+        "<p class=\"muted\">비디오 {}개, 비디오마다 댓글 최대 {}개, 자막 언어 {}</p>\n",
+        report.videos.len(),
+        report.options.comments,
+        escape(&report.options.languages.join(", "))
+    ));
+    if !report.failures.is_empty() {
+        html.push_str("<p class=\"muted\">가져오지 못한 비디오:</p>\n<ul>\n");
+        for failure in &report.failures {
+            html.push_str(&format!(
+                "<li>{} ({}): {}</li>\n",
+                escape(&failure.title),
+                escape(&failure.id),
+                escape(&failure.error)
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str(&recommendations(report));
+    for video in &report.videos {
+        html.push_str(&video_section(video));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn recommendations(report: &RunReport) -> String {
+    let mut html = String::from("<section>\n<h2>추천 순위</h2>\n");
+    if report.recommendations.is_empty() {
+        html.push_str("<p class=\"muted\">추천할 제품을 찾지 못했습니다.</p>\n</section>\n");
+        return html;
+    }
+
+    html.push_str("<table>\n<tr><th>#</th><th>제품</th><th>점수</th><th>비디오</th></tr>\n");
+    for (i, recommendation) in report.recommendations.iter().enumerate() {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{:.3}</td><td>{}</td></tr>\n",
+            i + 1,
+            escape(&recommendation.product),
+            recommendation.score,
+            recommendation.evidence.len()
+        ));
+    }
+    html.push_str("</table>\n");
+
+    for recommendation in &report.recommendations {
+        html.push_str(&product(report, recommendation));
+    }
+    html.push_str("</section>\n");
+    html
+}
+
+fn product(report: &RunReport, recommendation: &Recommendation) -> String {
+    let mut html = format!("<h3>{}</h3>\n", escape(&recommendation.product));
+
+    if !recommendation.aspects.is_empty() {
+        let bars: Vec<Bar> = recommendation
+            .aspects
+            .iter()
+            .map(|x| Bar {
+                label: &x.aspect,
+                positive: x.positive,
+                negative: x.negative,
+            })
+            .collect();
+        html.push_str(&sentiment_chart(&bars));
+    }
+
+    html.push_str("<ul>\n");
+    for evidence in &recommendation.evidence {
+        html.push_str(&format!(
+            "<li><a href=\"https://www.youtube.com/watch?v={}\">{}</a> <span class=\"muted\">{} · 기여도 {:.3}</span>",
+            escape(&evidence.video_id),
+            escape(&evidence.title),
+            escape(&evidence.channel_title),
+            evidence.contribution
+        ));
+        if let Some(video) = report.video(&evidence.video_id) {
+            html.push_str(&timestamps(
+                &video.id,
+                &video.product_segments(&recommendation.product),
+            ));
+        }
+        html.push_str("</li>\n");
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn video_section(video: &VideoAnalysis) -> String {
+    let mut html = format!(
+        "<section>\n<h2><a href=\"{}\">{}</a></h2>\n<p class=\"muted\">{} · {} · 조회수 {} · 좋아요 {}</p>\n",
+        escape(&video.url()),
+        escape(&video.title),
+        escape(&video.channel_title),
+        escape(&video.published_at),
+        count(video.view_count),
+        count(video.like_count)
+    );
+
+    if let Some(note) = video.transcript_outcome.note() {
+        html.push_str(&format!("<p class=\"muted\">{}</p>\n", escape(&note)));
+    }
+    if !video.summary.is_empty() {
+        html.push_str("<h3>요약</h3>\n");
+        for summary in &video.summary {
+            html.push_str(&format!("<p>{}</p>\n", escape(summary)));
+        }
+    }
+    if !video.answers.is_empty() {
+        html.push_str("<h3>주제와 결론</h3>\n<ul>\n");
+        for answer in &video.answers {
+            html.push_str(&format!(
+                "<li>{} <span class=\"muted\">({:.2})</span></li>\n",
+                escape(&answer.answer),
+                answer.score
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("<h3>댓글 감정</h3>\n");
+    let mut bars = vec![Bar {
+        label: "전체",
+        positive: video.positive_comments(),
+        negative: video.negative_comments(),
+    }];
+    bars.extend(video.aspects.iter().map(|x| Bar {
+        label: &x.aspect,
+        positive: x.positive,
+        negative: x.negative,
+    }));
+    html.push_str(&sentiment_chart(&bars));
+
+    let keywords = video.top_keywords(MAX_KEYWORDS);
+    if !keywords.is_empty() {
+        html.push_str("<h3>키워드</h3>\n<p class=\"keywords\">");
+        for keyword in keywords {
+            html.push_str(&format!(
+                "<span title=\"{}\">{}</span>",
+                escape(&keyword.translated_keyword),
+                escape(&keyword.keyword)
+            ));
+        }
+        html.push_str("</p>\n");
+    }
+
+    if !video.products.is_empty() {
+        html.push_str("<h3>언급된 제품</h3>\n<ul>\n");
+        for product in &video.products {
+            html.push_str(&format!(
+                "<li>{} <span class=\"muted\">×{}</span>{}</li>\n",
+                escape(&product.name),
+                product.count,
+                timestamps(&video.id, &video.product_segments(&product.name))
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    html.push_str("</section>\n");
+    html
+}
+
+/// links to the first segments, titled with their text.
+fn timestamps(video_id: &str, segments: &[&TranscriptCore]) -> String {
+    segments
+        .iter()
+        .take(MAX_TIMESTAMPS)
+        .map(|x| {
+            format!(
+                " <a href=\"{}\" title=\"{}\">{}</a>",
+                escape(&model::timestamp_url(video_id, x.start)),
+                escape(&x.text),
+                model::timestamp(x.start)
+            )
+        })
+        .collect()
+}
+
+/// horizontal stacked bars of positive and negative counts, scaled to the
+/// largest row.
+fn sentiment_chart(bars: &[Bar]) -> String {
+    let max = bars
+        .iter()
+        .map(|x| x.positive + x.negative)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    let width = LABEL_WIDTH + BAR_WIDTH + 80;
+    let height = bars.len() * (BAR_HEIGHT + 6);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" role=\"img\">\n",
+        width, height
+    );
+    for (i, bar) in bars.iter().enumerate() {
+        let y = i * (BAR_HEIGHT + 6);
+        let positive = bar.positive * BAR_WIDTH / max;
+        let negative = bar.negative * BAR_WIDTH / max;
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\" font-size=\"13\">{}</text>\n",
+            y + BAR_HEIGHT - 4,
+            escape(bar.label)
+        ));
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"><title>긍정 {}</title></rect>\n",
+            LABEL_WIDTH, y, positive, BAR_HEIGHT, POSITIVE_COLOR, bar.positive
+        ));
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"><title>부정 {}</title></rect>\n",
+            LABEL_WIDTH + positive,
+            y,
+            negative,
+            BAR_HEIGHT,
+            NEGATIVE_COLOR,
+            bar.negative
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"12\" fill=\"#777\">{} / {}</text>\n",
+            LABEL_WIDTH + positive + negative + 6,
+            y + BAR_HEIGHT - 4,
+            bar.positive,
+            bar.negative
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn count(value: Option<u64>) -> String {
+    value
+        .map(|x| x.to_string())
+        .unwrap_or_else(|| "-".to_owned())
+}
+
+/// escapes the characters with a meaning in HTML text and attributes.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,233 @@
+use crate::recommend::recommender::Recommendation;
+use crate::report::html::{MAX_KEYWORDS, MAX_TIMESTAMPS};
+use crate::report::model::{self, RunReport, VideoAnalysis};
+use crate::youtube_transcript::parser::TranscriptCore;
+
+/// characters of the longest sentiment bar
+const BAR_WIDTH: usize = 20;
+
+/// renders the report as Markdown. Sentiment charts are drawn with block
+/// characters since most Markdown viewers strip inline SVG.
+pub fn render(report: &RunReport) -> String {
+    let mut markdown = match &report.query {
+        Some(query) => format!("# 추천 리포트: {}\n\n", escape(query)),
+        None => "# 추천 리포트\n\n".to_owned(),
+    };
+    markdown.push_str(&format!(
+        "비디오 {}개, 비디오마다 댓글 최대 {}개, 자막 언어 {}\n\n",
+        report.videos.len(),
+        report.options.comments,
+        report.options.languages.join(", ")
+    ));
+    if !report.failures.is_empty() {
+        markdown.push_str("가져오지 못한 비디오:\n\n");
+        for failure in &report.failures {
+            markdown.push_str(&format!(
+                "- {} ({}): {}\n",
+                escape(&failure.title),
+                escape(&failure.id),
+                escape(&failure.error)
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    markdown.push_str("## 추천 순위\n\n");
+    if report.recommendations.is_empty() {
+        markdown.push_str("추천할 제품을 찾지 못했습니다.\n\n");
+    } else {
+        markdown.push_str("| # | 제품 | 점수 | 비디오 |\n|---|---|---|---|\n");
+        for (i, recommendation) in report.recommendations.iter().enumerate() {
+            markdown.push_str(&format!(
+                "| {} | {} | {:.3} | {} |\n",
+                i + 1,
+                escape(&recommendation.product),
+                recommendation.score,
+                recommendation.evidence.len()
+            ));
+        }
+        markdown.push('\n');
+
+        for recommendation in &report.recommendations {
+            markdown.push_str(&product(report, recommendation));
+        }
+    }
+
+    for video in &report.videos {
+        markdown.push_str(&video_section(video));
+    }
+    markdown
+}
+
+fn product(report: &RunReport, recommendation: &Recommendation) -> String {
+    let mut markdown = format!("### {}\n\n", escape(&recommendation.product));
+
+    if !recommendation.aspects.is_empty() {
+        let bars: Vec<(&str, usize, usize)> = recommendation
+            .aspects
+            .iter()
+            .map(|x| (x.aspect.as_str(), x.positive, x.negative))
+            .collect();
+        markdown.push_str(&sentiment_chart(&bars));
+    }
+
+    for evidence in &recommendation.evidence {
+        markdown.push_str(&format!(
+            "- [{}](https://www.youtube.com/watch?v={}) ({}, 기여도 {:.3})",
+            escape(&evidence.title),
+            evidence.video_id,
+            escape(&evidence.channel_title),
+            evidence.contribution
+        ));
+        if let Some(video) = report.video(&evidence.video_id) {
+            markdown.push_str(&timestamps(
+                &video.id,
+                &video.product_segments(&recommendation.product),
+            ));
+        }
+        markdown.push('\n');
+    }
+    markdown.push('\n');
+    markdown
+}
+
+fn video_section(video: &VideoAnalysis) -> String {
+    let mut markdown = format!(
+        "## [{}]({})\n\n{} · {} · 조회수 {} · 좋아요 {}\n\n",
+        escape(&video.title),
+        video.url(),
+        escape(&video.channel_title),
+        video.published_at,
+        count(video.view_count),
+        count(video.like_count)
+    );
+
+    if let Some(note) = video.transcript_outcome.note() {
+        markdown.push_str(&format!("{}\n\n", escape(&note)));
+    }
+    if !video.summary.is_empty() {
+        markdown.push_str("### 요약\n\n");
+        for summary in &video.summary {
+            markdown.push_str(&format!("{}\n\n", escape(summary)));
+        }
+    }
+    if !video.answers.is_empty() {
+        markdown.push_str("### 주제와 결론\n\n");
+        for answer in &video.answers {
+            markdown.push_str(&format!(
+                "- {} ({:.2})\n",
+                escape(&answer.answer),
+                answer.score
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    markdown.push_str("### 댓글 감정\n\n");
+    let mut bars = vec![("전체", video.positive_comments(), video.negative_comments())];
+    bars.extend(
+        video
+            .aspects
+            .iter()
+            .map(|x| (x.aspect.as_str(), x.positive, x.negative)),
+    );
+    markdown.push_str(&sentiment_chart(&bars));
+
+    let keywords = video.top_keywords(MAX_KEYWORDS);
+    if !keywords.is_empty() {
+        markdown.push_str("### 키워드\n\n");
+        markdown.push_str(
+            &keywords
+                .iter()
+                .map(|x| format!("`{}`", x.keyword.replace('`', "'")))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        markdown.push_str("\n\n");
+    }
+
+    if !video.products.is_empty() {
+        markdown.push_str("### 언급된 제품\n\n");
+        for product in &video.products {
+            markdown.push_str(&format!(
+                "- {} ×{}{}\n",
+                escape(&product.name),
+                product.count,
+                timestamps(&video.id, &video.product_segments(&product.name))
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    markdown
+}
+
+fn timestamps(video_id: &str, segments: &[&TranscriptCore]) -> String {
+    segments
+        .iter()
+        .take(MAX_TIMESTAMPS)
+        .map(|x| {
+            format!(
+                " [{}]({})",
+                model::timestamp(x.start),
+                model::timestamp_url(video_id, x.start)
+            )
+        })
+        .collect()
+}
+
+/// `(label, positive, negative)` rows drawn as "█" for positive and "░" for
+/// negative comments, scaled to the largest row.
+fn sentiment_chart(bars: &[(&str, usize, usize)]) -> String {
+    let max = bars
+        .iter()
+        .map(|(_, positive, negative)| positive + negative)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    let mut markdown = String::from("```\n");
+    let label_width = bars
+        .iter()
+        .map(|(label, _, _)| label.chars().count())
+        .max()
+        .unwrap_or_default();
+    for (label, positive, negative) in bars {
+        markdown.push_str(&format!(
+            "{}{} {}{} {} / {}\n",
+            label,
+            " ".repeat(label_width - label.chars().count()),
+            "█".repeat(positive * BAR_WIDTH / max),
+            "░".repeat(negative * BAR_WIDTH / max),
+            positive,
+            negative
+        ));
+    }
+    markdown.push_str("```\n\n");
+    markdown
+}
+
+fn count(value: Option<u64>) -> String {
+    value
+        .map(|x| x.to_string())
+        .unwrap_or_else(|| "-".to_owned())
+}
+
+/// escapes the characters that would start Markdown formatting or break a
+/// table cell.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        match c {
+            '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,4 @@
+pub mod html;
+pub mod markdown;
+pub mod model;
+pub mod writer;
@@ -0,0 +1,267 @@
+use crate::recommend::aspect::AspectSentiment;
+use crate::recommend::product::ProductEntity;
+use crate::recommend::recommender::{Recommendation, VideoSignals};
+use crate::youtube_transcript::parser::{Transcript, TranscriptCore};
+use rust_bert::pipelines::sentiment::{Sentiment, SentimentPolarity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Polarity of a comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Polarity {
+    Positive,
+    Negative,
+}
+
+/// Answer of the question answering model.
+#[derive(Debug, Clone, Serialize)]
+pub struct QaAnswer {
+    pub answer: String,
+    pub translated_answer: String,
+    pub score: f64,
+}
+
+/// Sentiment of a comment.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentSentiment {
+    pub comment: String,
+    pub translated_comment: String,
+    pub polarity: Polarity,
+    pub score: f64,
+}
+
+impl CommentSentiment {
+    pub fn new(comment: String, translated_comment: String, sentiment: &Sentiment) -> Self {
+        Self {
+            comment,
+            translated_comment,
+            polarity: match sentiment.polarity {
+                SentimentPolarity::Positive => Polarity::Positive,
+                SentimentPolarity::Negative => Polarity::Negative,
+            },
+            score: sentiment.score,
+        }
+    }
+}
+
+/// Keyword extracted from the translated text, with its translation back.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordScore {
+    pub keyword: String,
+    pub translated_keyword: String,
+    pub score: f32,
+}
+
+/// What happened fetching the transcript of a video. Without a transcript
+/// the video is analyzed from its comments only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TranscriptOutcome {
+    Ok,
+    /// no captions in the languages of the run
+    NoCaptions,
+    /// the watch page or the captions couldn't be fetched, Ex: a consent
+    /// page, a removed video
+    Unavailable {
+        reason: String,
+    },
+    /// the captions were fetched but couldn't be read
+    ParseFailed {
+        reason: String,
+    },
+}
+
+impl TranscriptOutcome {
+    /// line shown in the reports, `None` when the transcript was read.
+    pub fn note(&self) -> Option<String> {
+        match self {
+            TranscriptOutcome::Ok => None,
+            TranscriptOutcome::NoCaptions => Some("자막이 없습니다.".to_owned()),
+            TranscriptOutcome::Unavailable { reason } => {
+                Some(format!("자막을 가져오지 못했습니다: {}", reason))
+            }
+            TranscriptOutcome::ParseFailed { reason } => {
+                Some(format!("자막을 읽지 못했습니다: {}", reason))
+            }
+        }
+    }
+}
+
+/// Consecutive transcript segments translated and given to the models
+/// together.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptChunk {
+    /// start of the first segment in the video
+    pub start: Duration,
+    pub text: String,
+    pub translated_text: String,
+}
+
+/// Everything the pipeline found out about a single video.
+#[derive(Debug, Serialize)]
+pub struct VideoAnalysis {
+    pub id: String,
+    pub title: String,
+    pub channel_title: String,
+    pub published_at: String,
+    pub view_count: Option<u64>,
+    pub like_count: Option<u64>,
+    /// transcript segments with their timing and the caption track used
+    pub transcript: Transcript,
+    pub transcript_outcome: TranscriptOutcome,
+    pub script: String,
+    pub translated_script: String,
+    /// script split to fit the models, summarized and questioned chunk by chunk
+    pub chunks: Vec<ScriptChunk>,
+    /// theme and conclusion of the video
+    pub answers: Vec<QaAnswer>,
+    pub summary: Vec<String>,
+    pub translated_summary: Vec<String>,
+    pub sentiments: Vec<CommentSentiment>,
+    pub aspects: Vec<AspectSentiment>,
+    pub script_keywords: Vec<KeywordScore>,
+    pub comment_keywords: Vec<KeywordScore>,
+    pub products: Vec<ProductEntity>,
+    /// input of the recommender, not part of the report
+    #[serde(skip)]
+    pub signals: VideoSignals,
+}
+
+impl VideoAnalysis {
+    pub fn url(&self) -> String {
+        format!("https://www.youtube.com/watch?v={}", self.id)
+    }
+
+    pub fn positive_comments(&self) -> usize {
+        self.sentiments
+            .iter()
+            .filter(|x| x.polarity == Polarity::Positive)
+            .count()
+    }
+
+    pub fn negative_comments(&self) -> usize {
+        self.sentiments.len() - self.positive_comments()
+    }
+
+    /// transcript segments containing one of the terms, case-insensitively.
+    pub fn segments(&self, terms: &[&str]) -> Vec<&TranscriptCore> {
+        let terms: Vec<String> = terms
+            .iter()
+            .map(|x| x.trim().to_lowercase())
+            .filter(|x| !x.is_empty())
+            .collect();
+
+        self.transcript
+            .transcripts
+            .iter()
+            .filter(|segment| {
+                let text = segment.text.to_lowercase();
+                terms.iter().any(|term| text.contains(term.as_str()))
+            })
+            .collect()
+    }
+
+    /// script and comment keywords, highest score first.
+    pub fn top_keywords(&self, count: usize) -> Vec<&KeywordScore> {
+        let mut keywords: Vec<&KeywordScore> = self
+            .script_keywords
+            .iter()
+            .chain(&self.comment_keywords)
+            .collect();
+        keywords.sort_by(|a, b| b.score.total_cmp(&a.score));
+        let mut seen = HashSet::new();
+        keywords.retain(|x| seen.insert(x.keyword.to_lowercase()));
+        keywords.truncate(count);
+        keywords
+    }
+
+    /// transcript segments mentioning the product, with any of the spellings
+    /// merged into it.
+    pub fn product_segments(&self, product: &str) -> Vec<&TranscriptCore> {
+        let mut terms = vec![product];
+        if let Some(entity) = self
+            .products
+            .iter()
+            .find(|x| x.name.to_lowercase() == product.to_lowercase())
+        {
+            terms.extend(entity.mentions.iter().map(String::as_str));
+        }
+        self.segments(&terms)
+    }
+}
+
+/// Options a run was started with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunOptions {
+    pub videos: usize,
+    pub comments: usize,
+    pub languages: Vec<String>,
+}
+
+/// Video of a run whose comments couldn't be fetched, left out of the
+/// analyses.
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoFailure {
+    pub id: String,
+    pub title: String,
+    pub error: String,
+}
+
+/// Result of a whole run.
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    /// id of the run in the store, `None` when it is not stored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<i64>,
+    /// search query, `None` when videos were given by id
+    pub query: Option<String>,
+    pub options: RunOptions,
+    /// unix time in seconds
+    pub started_at: u64,
+    pub finished_at: u64,
+    /// shared with the job serving them while the run goes on
+    pub videos: Vec<Arc<VideoAnalysis>>,
+    /// videos that couldn't be fetched, the others are analyzed anyway
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<VideoFailure>,
+    pub recommendations: Vec<Recommendation>,
+}
+
+impl RunReport {
+    pub fn video(&self, id: &str) -> Option<&VideoAnalysis> {
+        self.videos.iter().find(|x| x.id == id).map(Arc::as_ref)
+    }
+}
+
+/// Record of a JSON Lines report. Videos are written as soon as they are
+/// analyzed, the recommendations last.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportLine<'a> {
+    Video(&'a VideoAnalysis),
+    Recommendations {
+        query: Option<&'a str>,
+        recommendations: &'a [Recommendation],
+    },
+}
+
+/// link to the video starting at `start`. Ex: "https://www.youtube.com/watch?v=ID&t=83s"
+pub fn timestamp_url(video_id: &str, start: Duration) -> String {
+    format!(
+        "https://www.youtube.com/watch?v={}&t={}s",
+        video_id,
+        start.as_secs()
+    )
+}
+
+/// `start` written as "m:ss", or "h:mm:ss" past an hour.
+pub fn timestamp(start: Duration) -> String {
+    let seconds = start.as_secs();
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+    }
+}
@@ -0,0 +1,97 @@
+use crate::report::model::{ReportLine, RunReport};
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Where a report is written: the standard output, a temporary file next to
+/// the report that replaces it on [`Output::finish`], or the report itself for
+/// the records streamed as JSON Lines. A run that fails leaves the previous
+/// report as it was, or the records it streamed.
+pub struct Output {
+    writer: Box<dyn Write>,
+    /// temporary file and the report it is renamed to
+    file: Option<(PathBuf, PathBuf)>,
+}
+
+impl Output {
+    pub fn stdout() -> Self {
+        Self {
+            writer: Box::new(io::stdout()),
+            file: None,
+        }
+    }
+
+    /// writes to `path`.tmp until [`Output::finish`]. Ex: "report.json.tmp"
+    pub fn create(path: &Path) -> Result<Self> {
+        let mut name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("not a file: {}", path.display()))?
+            .to_os_string();
+        name.push(".tmp");
+        let temp = path.with_file_name(name);
+        Ok(Self {
+            writer: Box::new(BufWriter::new(File::create(&temp)?)),
+            file: Some((temp, path.to_owned())),
+        })
+    }
+
+    /// writes straight to `path`, keeping what was written if the run fails.
+    pub fn stream(path: &Path) -> Result<Self> {
+        Ok(Self {
+            writer: Box::new(BufWriter::new(File::create(path)?)),
+            file: None,
+        })
+    }
+
+    /// flushes what was written and moves the file over the report.
+    pub fn finish(mut self) -> Result<()> {
+        self.writer.flush()?;
+        if let Some((temp, path)) = self.file.take() {
+            std::fs::rename(temp, path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        // 끝내지 못한 출력은 지운다.
+        if let Some((temp, _)) = self.file.take() {
+            let _ = std::fs::remove_file(temp);
+        }
+    }
+}
+
+/// writes the report as a single pretty printed JSON document.
+pub fn write_json(writer: &mut impl Write, report: &RunReport) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, report)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// writes a single JSON Lines record and flushes it.
+pub fn write_json_line(writer: &mut impl Write, line: &ReportLine) -> Result<()> {
+    serde_json::to_writer(&mut *writer, line)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// writes a rendered report.
+pub fn write_text(writer: &mut impl Write, text: &str) -> Result<()> {
+    writer.write_all(text.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
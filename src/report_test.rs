@@ -0,0 +1,160 @@
+use crate::recommend::product::ProductEntity;
+use crate::recommend::recommender::{Recommender, VideoSignals};
+use crate::report::model::{
+    self, ReportLine, RunOptions, RunReport, TranscriptOutcome, VideoAnalysis,
+};
+use crate::report::writer::{self, Output};
+use crate::report::{html, markdown};
+use crate::youtube_transcript::parser::{Transcript, TranscriptCore};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn segment(start: u64, text: &str) -> TranscriptCore {
+    TranscriptCore {
+        text: text.to_owned(),
+        start: Duration::from_secs(start),
+        duration: Duration::from_secs(3),
+        words: Vec::new(),
+    }
+}
+
+fn report() -> RunReport {
+    let mut signals = VideoSignals {
+        video_id: "abc".to_owned(),
+        title: "<S23> 리뷰".to_owned(),
+        view_count: Some(1000),
+        like_count: Some(100),
+        sentiment: 0.5,
+        ..Default::default()
+    };
+    signals.add_mention("Galaxy S23", 2.0);
+
+    let video = VideoAnalysis {
+        id: "abc".to_owned(),
+        title: "<S23> 리뷰".to_owned(),
+        channel_title: "채널".to_owned(),
+        published_at: "2024-01-01T00:00:00Z".to_owned(),
+        view_count: Some(1000),
+        like_count: Some(100),
+        transcript: Transcript {
+            transcripts: vec![
+                segment(0, "안녕하세요"),
+                segment(83, "갤럭시 S23 배터리는"),
+                segment(3725, "S23 울트라 카메라"),
+            ],
+            caption: None,
+        },
+        transcript_outcome: TranscriptOutcome::Ok,
+        script: String::new(),
+        translated_script: String::new(),
+        chunks: Vec::new(),
+        answers: Vec::new(),
+        summary: vec!["배터리가 좋다".to_owned()],
+        translated_summary: Vec::new(),
+        sentiments: Vec::new(),
+        aspects: Vec::new(),
+        script_keywords: Vec::new(),
+        comment_keywords: Vec::new(),
+        products: vec![ProductEntity {
+            name: "Galaxy S23".to_owned(),
+            mentions: vec!["갤럭시 S23".to_owned(), "S23 울트라".to_owned()],
+            count: 2,
+            score: 2.0,
+        }],
+        signals,
+    };
+
+    RunReport {
+        run_id: None,
+        query: Some("갤럭시".to_owned()),
+        options: RunOptions {
+            videos: 1,
+            comments: 10,
+            languages: vec!["ko".to_owned()],
+        },
+        started_at: 0,
+        finished_at: 0,
+        recommendations: Recommender::default().rank(std::slice::from_ref(&video.signals)),
+        videos: vec![Arc::new(video)],
+        failures: Vec::new(),
+    }
+}
+
+#[test]
+fn report_timestamp_test() {
+    assert_eq!(model::timestamp(Duration::from_secs(83)), "1:23");
+    assert_eq!(model::timestamp(Duration::from_secs(3725)), "1:02:05");
+    assert_eq!(
+        model::timestamp_url("abc", Duration::from_millis(83_900)),
+        "https://www.youtube.com/watch?v=abc&t=83s"
+    );
+
+    let report = report();
+    let segments = report.videos[0].product_segments("galaxy s23");
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].start, Duration::from_secs(83));
+}
+
+#[test]
+fn report_render_test() {
+    let report = report();
+
+    let html = html::render(&report);
+    println!("{html}");
+    assert!(html.contains("&lt;S23&gt; 리뷰"));
+    assert!(!html.contains("<S23>"));
+    assert!(html.contains("https://www.youtube.com/watch?v=abc&amp;t=83s"));
+    assert!(html.contains("<svg"));
+
+    let markdown = markdown::render(&report);
+    println!("{markdown}");
+    assert!(markdown.contains("\\<S23\\> 리뷰"));
+    assert!(markdown.contains("[1:23](https://www.youtube.com/watch?v=abc&t=83s)"));
+    assert!(markdown.contains("| 1 | Galaxy S23 |"));
+}
+
+#[test]
+fn report_output_test() {
+    let dir = std::env::temp_dir().join(format!("youtube_recommend_output_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("report.json");
+    std::fs::write(&path, "이전 리포트").unwrap();
+
+    // 실패한 실행은 이전 리포트를 그대로 둔다.
+    let mut output = Output::create(&path).unwrap();
+    write!(output, "{{").unwrap();
+    drop(output);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "이전 리포트");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    let mut output = Output::create(&path).unwrap();
+    writer::write_json(&mut output, &report()).unwrap();
+    output.finish().unwrap();
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written["query"], "갤럭시");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    // json lines 는 바로 쓰고, 실패한 실행이 쓴 기록도 남긴다.
+    let path = dir.join("report.jsonl");
+    let mut output = Output::stream(&path).unwrap();
+    writer::write_json_line(
+        &mut output,
+        &ReportLine::Recommendations {
+            query: None,
+            recommendations: &[],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "{\"type\":\"recommendations\",\"query\":null,\"recommendations\":[]}\n"
+    );
+    drop(output);
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .starts_with("{\"type\":\"recommendations\""));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,31 @@
+use crate::server::handler;
+use crate::server::state::SharedState;
+use axum::routing::{get, post};
+use axum::Router;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+
+pub fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/analyze", post(handler::analyze))
+        .route("/jobs", get(handler::jobs))
+        .route("/jobs/:id", get(handler::job).delete(handler::cancel_job))
+        .route("/jobs/:id/result", get(handler::job_result))
+        .route("/jobs/:id/videos", get(handler::job_videos))
+        .route("/videos/:id/transcript", get(handler::transcript))
+        .route("/videos/:id/sentiment", get(handler::sentiment))
+        .with_state(state)
+}
+
+/// serves the api until ctrl-c.
+pub async fn serve(addr: SocketAddr, state: SharedState) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    println!("listening on http://{}", listener.local_addr()?);
+
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+    Ok(())
+}
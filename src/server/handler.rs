@@ -0,0 +1,262 @@
+use crate::error::Error;
+use crate::job::progress::{Progress, Stage};
+use crate::job::queue::{Job, JobSummary};
+use crate::pipeline::{self, Context};
+use crate::recommend::aspect::AspectSentiment;
+use crate::report::model::{CommentSentiment, Polarity, RunOptions, RunReport, VideoAnalysis};
+use crate::server::state::SharedState;
+use crate::youtube_transcript::parser::Transcript;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Body of `POST /analyze`. Missing options fall back to the ones the server
+/// was started with.
+#[derive(Debug, Deserialize)]
+pub struct AnalyzeRequest {
+    pub query: String,
+    pub videos: Option<usize>,
+    pub comments: Option<usize>,
+    pub languages: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SentimentQuery {
+    pub comments: Option<usize>,
+}
+
+/// Body of `GET /videos/{id}/sentiment`.
+#[derive(Debug, Serialize)]
+pub struct SentimentResponse {
+    pub video_id: String,
+    pub positive: usize,
+    pub negative: usize,
+    pub comments: Vec<CommentSentiment>,
+    pub aspects: Vec<AspectSentiment>,
+}
+
+/// Error answered as `{"error": "..."}` with a status matching its cause. A
+/// failed upstream request is a `502` telling the status of the upstream in
+/// `upstream_status`, the client of this api being not to blame.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+    upstream_status: Option<u16>,
+}
+
+impl ApiError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: message.into(),
+            upstream_status: None,
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(value: Error) -> Self {
+        let upstream_status = match &value {
+            Error::Http { status, .. } => Some(status.as_u16()),
+            _ => None,
+        };
+        let status = match &value {
+            Error::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+            Error::CommentsDisabled(_) | Error::TranscriptUnavailable(_) => StatusCode::NOT_FOUND,
+            Error::Http { .. }
+            | Error::TranslationFailed(_)
+            | Error::Request(_)
+            | Error::Json(_) => StatusCode::BAD_GATEWAY,
+            Error::ModelLoadFailed(_) | Error::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self {
+            status,
+            message: value.to_string(),
+            upstream_status,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(value: anyhow::Error) -> Self {
+        match value.downcast::<Error>() {
+            Ok(error) => error.into(),
+            Err(error) => Self {
+                status: StatusCode::INTERNAL_SERVER_ERROR,
+                message: error.to_string(),
+                upstream_status: None,
+            },
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = match self.upstream_status {
+            Some(upstream_status) => {
+                json!({ "error": self.message, "upstream_status": upstream_status })
+            }
+            None => json!({ "error": self.message }),
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// `POST /analyze`: queues the search and the analysis of the videos of the
+/// query and answers the id of the job to poll.
+pub async fn analyze(
+    State(state): State<SharedState>,
+    Json(request): Json<AnalyzeRequest>,
+) -> (StatusCode, Json<Value>) {
+    let options = RunOptions {
+        videos: request.videos.unwrap_or(state.options.videos),
+        comments: request.comments.unwrap_or(state.options.comments),
+        languages: request
+            .languages
+            .unwrap_or_else(|| state.options.languages.clone()),
+    };
+    let runner = state.clone();
+    let query = request.query.clone();
+    let id = state
+        .jobs
+        .submit(&request.query, options.clone(), move |job| async move {
+            run(&runner, &query, options, job.as_ref()).await
+        });
+
+    (StatusCode::ACCEPTED, Json(json!({ "job_id": id })))
+}
+
+async fn run(
+    state: &SharedState,
+    query: &str,
+    options: RunOptions,
+    progress: &Job,
+) -> anyhow::Result<RunReport> {
+    progress.start(Stage::Search, None);
+    // 비디오 정보는 pipeline::run 이 가져오므로 검색만 한다.
+    let ids = pipeline::search_video_ids(&state.youtube_api, query, options.videos).await?;
+    progress.finish(Stage::Search);
+
+    let context = Context {
+        youtube_api: &state.youtube_api,
+        analyzer: &state.analyzer,
+        store: state.store.as_ref(),
+        progress,
+    };
+    pipeline::run(&context, Some(query), &ids, options, |analysis| {
+        progress.add_analysis(analysis.clone());
+        Ok(())
+    })
+    .await
+}
+
+/// `GET /jobs`
+pub async fn jobs(State(state): State<SharedState>) -> Json<Vec<JobSummary>> {
+    Json(state.jobs.list())
+}
+
+/// `GET /jobs/{id}`: status and progress of every stage.
+pub async fn job(
+    State(state): State<SharedState>,
+    Path(id): Path<u64>,
+) -> Result<Json<JobSummary>, ApiError> {
+    Ok(Json(find_job(&state, id)?.summary()))
+}
+
+/// `GET /jobs/{id}/result`: report of a finished job.
+pub async fn job_result(
+    State(state): State<SharedState>,
+    Path(id): Path<u64>,
+) -> Result<Response, ApiError> {
+    let job = find_job(&state, id)?;
+    let report = job.report().ok_or_else(|| {
+        let summary = job.summary();
+        ApiError {
+            status: StatusCode::CONFLICT,
+            message: match summary.error {
+                Some(error) => format!("job {} failed: {}", id, error),
+                None => format!("job {} is {:?}", id, summary.status).to_lowercase(),
+            },
+            upstream_status: None,
+        }
+    })?;
+    Ok(Json(report.as_ref()).into_response())
+}
+
+/// `GET /jobs/{id}/videos`: videos analyzed so far, while the job runs.
+pub async fn job_videos(
+    State(state): State<SharedState>,
+    Path(id): Path<u64>,
+) -> Result<Json<Vec<Arc<VideoAnalysis>>>, ApiError> {
+    Ok(Json(find_job(&state, id)?.analyses()))
+}
+
+/// `DELETE /jobs/{id}`: cancels a queued or running job.
+pub async fn cancel_job(
+    State(state): State<SharedState>,
+    Path(id): Path<u64>,
+) -> Result<Json<Value>, ApiError> {
+    let status = state
+        .jobs
+        .cancel(id)
+        .ok_or_else(|| ApiError::not_found(format!("no job {}", id)))?;
+    Ok(Json(json!({ "job_id": id, "status": status })))
+}
+
+fn find_job(state: &SharedState, id: u64) -> Result<Arc<Job>, ApiError> {
+    state
+        .jobs
+        .get(id)
+        .ok_or_else(|| ApiError::not_found(format!("no job {}", id)))
+}
+
+/// `GET /videos/{id}/transcript`
+pub async fn transcript(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+) -> Result<Json<Transcript>, ApiError> {
+    Ok(Json(
+        pipeline::get_transcript(
+            state.youtube_api.cache(),
+            state.youtube_api.rate_limiter(),
+            state.youtube_api.web_url(),
+            &state.options.languages,
+            &id,
+        )
+        .await?,
+    ))
+}
+
+/// `GET /videos/{id}/sentiment?comments=100`
+pub async fn sentiment(
+    State(state): State<SharedState>,
+    Path(id): Path<String>,
+    Query(query): Query<SentimentQuery>,
+) -> Result<Json<SentimentResponse>, ApiError> {
+    let max_results = query.comments.unwrap_or(state.options.comments);
+    let comments = pipeline::get_comments(&state.youtube_api, &id, max_results)
+        .await?
+        .iter()
+        .map(|x| x.text().to_string())
+        .collect();
+    let (comments, aspects) = state.analyzer.comment_sentiments(comments).await?;
+
+    Ok(Json(SentimentResponse {
+        video_id: id,
+        positive: comments
+            .iter()
+            .filter(|x| x.polarity == Polarity::Positive)
+            .count(),
+        negative: comments
+            .iter()
+            .filter(|x| x.polarity == Polarity::Negative)
+            .count(),
+        comments,
+        aspects,
+    }))
+}
@@ -0,0 +1,19 @@
+use crate::job::queue::JobQueue;
+use crate::pipeline::Analyzer;
+use crate::report::model::RunOptions;
+use crate::storage::store::Store;
+use crate::youtube_api::client::YoutubeApiClient;
+use std::sync::Arc;
+
+/// State shared by the handlers.
+pub struct AppState {
+    pub youtube_api: YoutubeApiClient,
+    pub analyzer: Analyzer,
+    /// keeps the runs of the jobs when given
+    pub store: Option<Store>,
+    /// options used when a request leaves them out
+    pub options: RunOptions,
+    pub jobs: JobQueue,
+}
+
+pub type SharedState = Arc<AppState>;
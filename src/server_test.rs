@@ -0,0 +1,35 @@
+use crate::error::Error;
+use crate::server::handler::ApiError;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+#[tokio::test]
+async fn server_api_error_test() {
+    let status = |error: ApiError| error.into_response().status();
+
+    assert_eq!(
+        status(Error::QuotaExceeded("quota".to_owned()).into()),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    assert_eq!(
+        status(anyhow::Error::from(Error::CommentsDisabled("abc".to_owned())).into()),
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status(anyhow::anyhow!("model worker has stopped").into()),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+
+    // 외부 api 가 거절한 요청은 클라이언트 탓이 아니다.
+    let response = ApiError::from(Error::Http {
+        status: reqwest::StatusCode::FORBIDDEN,
+        body: "forbidden".to_owned(),
+    })
+    .into_response();
+    assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["upstream_status"], 403);
+}
@@ -0,0 +1,3 @@
+pub mod schema;
+pub mod store;
+pub mod translator;
@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+
+/// Migrations in order, `MIGRATIONS[i]` bringing the schema from version `i`
+/// to `i + 1`. Applied migrations are never edited, changes go in a new one.
+const MIGRATIONS: &[&str] = &[
+    // 1: 비디오, 채널, 댓글, 자막, 번역, 실행별 분석 결과
+    "
+    CREATE TABLE channels (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        subscriber_count INTEGER,
+        video_count INTEGER,
+        raw TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE TABLE videos (
+        id TEXT PRIMARY KEY,
+        channel_id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        published_at TEXT NOT NULL,
+        view_count INTEGER,
+        like_count INTEGER,
+        comment_count INTEGER,
+        raw TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE TABLE comment_threads (
+        id TEXT PRIMARY KEY,
+        video_id TEXT NOT NULL,
+        author TEXT NOT NULL,
+        text TEXT NOT NULL,
+        like_count INTEGER,
+        published_at TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE INDEX comment_threads_video_id ON comment_threads (video_id);
+    CREATE TABLE transcripts (
+        video_id TEXT PRIMARY KEY,
+        lang_code TEXT,
+        kind TEXT,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE TABLE transcript_segments (
+        video_id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        start_ms INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        PRIMARY KEY (video_id, position)
+    );
+    CREATE TABLE translations (
+        source_lang TEXT NOT NULL,
+        target_lang TEXT NOT NULL,
+        text TEXT NOT NULL,
+        translation TEXT NOT NULL,
+        PRIMARY KEY (source_lang, target_lang, text)
+    );
+    CREATE TABLE runs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        query TEXT,
+        options TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        finished_at INTEGER
+    );
+    CREATE TABLE analyses (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        video_id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        script TEXT NOT NULL,
+        translated_script TEXT NOT NULL,
+        summary TEXT NOT NULL,
+        translated_summary TEXT NOT NULL,
+        PRIMARY KEY (run_id, video_id)
+    );
+    CREATE TABLE answers (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        video_id TEXT NOT NULL,
+        answer TEXT NOT NULL,
+        translated_answer TEXT NOT NULL,
+        score REAL NOT NULL
+    );
+    CREATE TABLE sentiments (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        video_id TEXT NOT NULL,
+        comment TEXT NOT NULL,
+        translated_comment TEXT NOT NULL,
+        polarity TEXT NOT NULL,
+        score REAL NOT NULL
+    );
+    CREATE TABLE aspects (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        video_id TEXT NOT NULL,
+        aspect TEXT NOT NULL,
+        positive INTEGER NOT NULL,
+        negative INTEGER NOT NULL,
+        score REAL NOT NULL
+    );
+    CREATE TABLE keywords (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        video_id TEXT NOT NULL,
+        source TEXT NOT NULL,
+        keyword TEXT NOT NULL,
+        translated_keyword TEXT NOT NULL,
+        score REAL NOT NULL
+    );
+    CREATE TABLE products (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        video_id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        mentions TEXT NOT NULL,
+        count INTEGER NOT NULL,
+        score REAL NOT NULL
+    );
+    CREATE TABLE recommendations (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        rank INTEGER NOT NULL,
+        product TEXT NOT NULL,
+        score REAL NOT NULL,
+        raw TEXT NOT NULL,
+        PRIMARY KEY (run_id, rank)
+    );
+    CREATE INDEX recommendations_product ON recommendations (product COLLATE NOCASE);
+    ",
+    // 2: 실행마다 분석한 댓글과 그 순서
+    "
+    CREATE TABLE run_comments (
+        run_id INTEGER NOT NULL REFERENCES runs (id),
+        video_id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        comment_id TEXT NOT NULL,
+        PRIMARY KEY (run_id, video_id, position)
+    );
+    ",
+];
+
+/// version the migrations bring the schema to.
+pub const VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// version of the schema, kept in `PRAGMA user_version`.
+pub fn version(conn: &Connection) -> Result<i64> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+/// applies the missing migrations, each in its own transaction.
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    let current = version(conn)?;
+    if current > VERSION {
+        return Err(anyhow!(
+            "database schema version {} is newer than {}, update the program",
+            current,
+            VERSION
+        ));
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", i as i64 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
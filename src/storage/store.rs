@@ -0,0 +1,548 @@
+use crate::report::model::{RunOptions, RunReport, VideoAnalysis};
+use crate::storage::schema;
+use crate::time::unix_time;
+use crate::youtube_api::model::{Channel, CommentThread, Video};
+use crate::youtube_transcript::parser::{Caption, Transcript, TranscriptCore};
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Run as listed by [`Store::runs`].
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub id: i64,
+    pub query: Option<String>,
+    /// unix time in seconds
+    pub started_at: u64,
+    /// `None` if the run failed or is still going
+    pub finished_at: Option<u64>,
+    pub videos: usize,
+    /// best ranked product
+    pub top_product: Option<String>,
+}
+
+/// Score of a product in a run, see [`Store::product_history`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductScore {
+    pub run_id: i64,
+    pub query: Option<String>,
+    pub started_at: u64,
+    /// 1 for the best ranked product
+    pub rank: usize,
+    pub score: f64,
+}
+
+/// SQLite storage of the fetched data and of the analysis of every run.
+/// Clones share the same connection.
+#[derive(Debug, Clone)]
+pub struct Store {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl Store {
+    /// opens the database at `path`, creating and migrating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// opens the database at `DATABASE_PATH`, `youtube_recommend.db` by default.
+    pub fn from_env() -> Result<Self> {
+        Self::open(
+            std::env::var("DATABASE_PATH").unwrap_or_else(|_| "youtube_recommend.db".to_owned()),
+        )
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        schema::migrate(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    pub fn schema_version(&self) -> Result<i64> {
+        schema::version(&self.conn.lock().unwrap())
+    }
+
+    /// inserts or updates the videos.
+    pub fn save_videos(&self, videos: &[Video]) -> Result<()> {
+        self.transaction(|tx| {
+            for video in videos {
+                tx.execute(
+                    "INSERT OR REPLACE INTO videos
+                     (id, channel_id, title, published_at, view_count, like_count, comment_count, raw, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        video.id,
+                        video.snippet.channel_id,
+                        video.snippet.title,
+                        video.snippet.published_at,
+                        video.statistics.view_count,
+                        video.statistics.like_count,
+                        video.statistics.comment_count,
+                        serde_json::to_string(video)?,
+                        unix_time(),
+                    ],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// ids of the channels that are not stored yet.
+    pub fn missing_channels(&self, ids: &[String]) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare("SELECT EXISTS (SELECT 1 FROM channels WHERE id = ?1)")?;
+        let mut missing = Vec::new();
+        for id in ids {
+            if !statement.query_row(params![id], |row| row.get::<_, bool>(0))? {
+                missing.push(id.clone());
+            }
+        }
+        Ok(missing)
+    }
+
+    /// inserts or updates the channels.
+    pub fn save_channels(&self, channels: &[Channel]) -> Result<()> {
+        self.transaction(|tx| {
+            for channel in channels {
+                tx.execute(
+                    "INSERT OR REPLACE INTO channels
+                     (id, title, subscriber_count, video_count, raw, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        channel.id,
+                        channel.snippet.title,
+                        channel.statistics.subscriber_count,
+                        channel.statistics.video_count,
+                        serde_json::to_string(channel)?,
+                        unix_time(),
+                    ],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// inserts or updates the comment threads of a video, and records them as
+    /// the comments `run_id` analyzed, in order.
+    pub fn save_comments(
+        &self,
+        run_id: i64,
+        video_id: &str,
+        comments: &[CommentThread],
+    ) -> Result<()> {
+        self.transaction(|tx| {
+            for (position, comment) in comments.iter().enumerate() {
+                let snippet = &comment.snippet.top_level_comment.snippet;
+                tx.execute(
+                    "INSERT INTO comment_threads
+                     (id, video_id, author, text, like_count, published_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                     ON CONFLICT (id) DO UPDATE SET
+                     author = excluded.author, text = excluded.text,
+                     like_count = excluded.like_count, updated_at = excluded.updated_at",
+                    params![
+                        comment.id,
+                        video_id,
+                        snippet.author_display_name,
+                        comment.text(),
+                        snippet.like_count,
+                        snippet.published_at,
+                        unix_time(),
+                    ],
+                )?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO run_comments (run_id, video_id, position, comment_id)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![run_id, video_id, position, comment.id],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// records the comments `from` analyzed as the ones of `to`, for a run
+    /// reading the text of another.
+    pub fn copy_run_comments(&self, from: i64, to: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO run_comments (run_id, video_id, position, comment_id)
+             SELECT ?2, video_id, position, comment_id FROM run_comments WHERE run_id = ?1",
+            params![from, to],
+        )?;
+        Ok(())
+    }
+
+    /// replaces the transcript of a video.
+    pub fn save_transcript(&self, video_id: &str, transcript: &Transcript) -> Result<()> {
+        self.transaction(|tx| {
+            let caption = transcript.caption.as_ref();
+            tx.execute(
+                "INSERT OR REPLACE INTO transcripts (video_id, lang_code, kind, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    video_id,
+                    caption.map(|x| &x.lang_code),
+                    caption.and_then(|x| x.kind.as_ref()),
+                    unix_time(),
+                ],
+            )?;
+            tx.execute(
+                "DELETE FROM transcript_segments WHERE video_id = ?1",
+                params![video_id],
+            )?;
+            for (position, segment) in transcript.transcripts.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO transcript_segments (video_id, position, start_ms, duration_ms, text)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        video_id,
+                        position,
+                        segment.start.as_millis() as u64,
+                        segment.duration.as_millis() as u64,
+                        segment.text,
+                    ],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// records the start of a run and returns its id.
+    pub fn start_run(&self, query: Option<&str>, options: &RunOptions) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO runs (query, options, started_at) VALUES (?1, ?2, ?3)",
+            params![query, serde_json::to_string(options)?, unix_time()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// stores the analysis of a video in a run, and the translations it made.
+    pub fn save_analysis(&self, run_id: i64, analysis: &VideoAnalysis) -> Result<()> {
+        self.transaction(|tx| {
+            let video_id = &analysis.id;
+            let position: usize = tx.query_row(
+                "SELECT COUNT(*) FROM analyses WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )?;
+            tx.execute(
+                "INSERT OR REPLACE INTO analyses
+                 (run_id, video_id, position, script, translated_script, summary, translated_summary)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    run_id,
+                    video_id,
+                    position,
+                    analysis.script,
+                    analysis.translated_script,
+                    serde_json::to_string(&analysis.summary)?,
+                    serde_json::to_string(&analysis.translated_summary)?,
+                ],
+            )?;
+
+            for answer in &analysis.answers {
+                tx.execute(
+                    "INSERT INTO answers (run_id, video_id, answer, translated_answer, score)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        run_id,
+                        video_id,
+                        answer.answer,
+                        answer.translated_answer,
+                        answer.score
+                    ],
+                )?;
+            }
+            for sentiment in &analysis.sentiments {
+                tx.execute(
+                    "INSERT INTO sentiments (run_id, video_id, comment, translated_comment, polarity, score)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        run_id,
+                        video_id,
+                        sentiment.comment,
+                        sentiment.translated_comment,
+                        serde_json::to_value(sentiment.polarity)?.as_str(),
+                        sentiment.score,
+                    ],
+                )?;
+            }
+            for aspect in &analysis.aspects {
+                tx.execute(
+                    "INSERT INTO aspects (run_id, video_id, aspect, positive, negative, score)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        run_id,
+                        video_id,
+                        aspect.aspect,
+                        aspect.positive,
+                        aspect.negative,
+                        aspect.score
+                    ],
+                )?;
+            }
+            let keywords = analysis
+                .script_keywords
+                .iter()
+                .map(|x| ("script", x))
+                .chain(analysis.comment_keywords.iter().map(|x| ("comment", x)));
+            for (source, keyword) in keywords {
+                tx.execute(
+                    "INSERT INTO keywords (run_id, video_id, source, keyword, translated_keyword, score)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        run_id,
+                        video_id,
+                        source,
+                        keyword.keyword,
+                        keyword.translated_keyword,
+                        keyword.score
+                    ],
+                )?;
+            }
+            for product in &analysis.products {
+                tx.execute(
+                    "INSERT INTO products (run_id, video_id, name, mentions, count, score)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        run_id,
+                        video_id,
+                        product.name,
+                        serde_json::to_string(&product.mentions)?,
+                        product.count,
+                        product.score
+                    ],
+                )?;
+            }
+
+            // 원문 -> 영문 번역만 다시 쓸 수 있다. 모델 출력의 역번역은 입력이 매번 달라진다.
+            let translations = analysis
+                .chunks
+                .iter()
+                .map(|x| (&x.text, &x.translated_text))
+                .chain(
+                    analysis
+                        .sentiments
+                        .iter()
+                        .map(|x| (&x.comment, &x.translated_comment)),
+                );
+            for (text, translation) in translations {
+                tx.execute(
+                    "INSERT OR REPLACE INTO translations (source_lang, target_lang, text, translation)
+                     VALUES ('KO', 'EN', ?1, ?2)",
+                    params![text, translation],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// records the recommendations and the end of a run.
+    pub fn finish_run(&self, run_id: i64, report: &RunReport) -> Result<()> {
+        self.transaction(|tx| {
+            for (rank, recommendation) in report.recommendations.iter().enumerate() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO recommendations (run_id, rank, product, score, raw)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        run_id,
+                        rank + 1,
+                        recommendation.product,
+                        recommendation.score,
+                        serde_json::to_string(recommendation)?,
+                    ],
+                )?;
+            }
+            tx.execute(
+                "UPDATE runs SET finished_at = ?2 WHERE id = ?1",
+                params![run_id, report.finished_at],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// most recent runs first.
+    pub fn runs(&self, limit: usize) -> Result<Vec<RunSummary>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT id, query, started_at, finished_at,
+                    (SELECT COUNT(*) FROM analyses WHERE run_id = runs.id),
+                    (SELECT product FROM recommendations WHERE run_id = runs.id AND rank = 1)
+             FROM runs ORDER BY id DESC LIMIT ?1",
+        )?;
+        let runs = statement
+            .query_map(params![limit], |row| {
+                Ok(RunSummary {
+                    id: row.get(0)?,
+                    query: row.get(1)?,
+                    started_at: row.get(2)?,
+                    finished_at: row.get(3)?,
+                    videos: row.get(4)?,
+                    top_product: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(runs)
+    }
+
+    /// query and options a run was started with, `None` if there is no such run.
+    pub fn run(&self, run_id: i64) -> Result<Option<(Option<String>, RunOptions)>> {
+        let conn = self.conn.lock().unwrap();
+        let run: Option<(Option<String>, String)> = conn
+            .query_row(
+                "SELECT query, options FROM runs WHERE id = ?1",
+                params![run_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        run.map(|(query, options)| Ok((query, serde_json::from_str(&options)?)))
+            .transpose()
+    }
+
+    /// videos analyzed in a run, in the order they were analyzed.
+    pub fn run_videos(&self, run_id: i64) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement =
+            conn.prepare("SELECT video_id FROM analyses WHERE run_id = ?1 ORDER BY position")?;
+        let ids = statement
+            .query_map(params![run_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(ids)
+    }
+
+    pub fn video(&self, video_id: &str) -> Result<Option<Video>> {
+        let conn = self.conn.lock().unwrap();
+        let raw: Option<String> = conn
+            .query_row(
+                "SELECT raw FROM videos WHERE id = ?1",
+                params![video_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(raw.map(|x| serde_json::from_str(&x)).transpose()?)
+    }
+
+    /// stored transcript of a video, `None` if it was never fetched.
+    pub fn transcript(&self, video_id: &str) -> Result<Option<Transcript>> {
+        let conn = self.conn.lock().unwrap();
+        let caption: Option<(Option<String>, Option<String>)> = conn
+            .query_row(
+                "SELECT lang_code, kind FROM transcripts WHERE video_id = ?1",
+                params![video_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((lang_code, kind)) = caption else {
+            return Ok(None);
+        };
+
+        let mut statement = conn.prepare(
+            "SELECT start_ms, duration_ms, text FROM transcript_segments
+             WHERE video_id = ?1 ORDER BY position",
+        )?;
+        let transcripts = statement
+            .query_map(params![video_id], |row| {
+                Ok(TranscriptCore {
+                    start: Duration::from_millis(row.get(0)?),
+                    duration: Duration::from_millis(row.get(1)?),
+                    text: row.get(2)?,
+                    words: Vec::new(),
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(Some(Transcript {
+            transcripts,
+            caption: lang_code.map(|lang_code| Caption {
+                base_url: String::new(),
+                lang_code,
+                kind,
+            }),
+        }))
+    }
+
+    /// texts of the comments of a video analyzed in `run_id`, in order, up to
+    /// `max_results`. Runs stored before the comments of each run were
+    /// recorded read the most recent stored comments instead.
+    pub fn comments(&self, run_id: i64, video_id: &str, max_results: usize) -> Result<Vec<String>> {
+        let conn = self.conn.lock().unwrap();
+        let recorded: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM run_comments WHERE run_id = ?1)",
+            params![run_id],
+            |row| row.get(0),
+        )?;
+        let mut statement;
+        let comments = if recorded {
+            statement = conn.prepare(
+                "SELECT c.text FROM run_comments r JOIN comment_threads c ON c.id = r.comment_id
+                 WHERE r.run_id = ?1 AND r.video_id = ?2 ORDER BY r.position LIMIT ?3",
+            )?;
+            statement
+                .query_map(params![run_id, video_id, max_results], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?
+        } else {
+            statement = conn.prepare(
+                "SELECT text FROM comment_threads WHERE video_id = ?1
+                 ORDER BY published_at DESC LIMIT ?2",
+            )?;
+            statement
+                .query_map(params![video_id, max_results], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?
+        };
+        Ok(comments)
+    }
+
+    pub fn translation(&self, text: &str, source: &str, target: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn
+            .query_row(
+                "SELECT translation FROM translations
+                 WHERE source_lang = ?1 AND target_lang = ?2 AND text = ?3",
+                params![source, target, text],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// rank and score of a product in every run that recommended it, oldest
+    /// first. Product names are compared case-insensitively.
+    pub fn product_history(&self, product: &str) -> Result<Vec<ProductScore>> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT runs.id, runs.query, runs.started_at, recommendations.rank, recommendations.score
+             FROM recommendations JOIN runs ON runs.id = recommendations.run_id
+             WHERE recommendations.product = ?1 COLLATE NOCASE
+             ORDER BY runs.id",
+        )?;
+        let history = statement
+            .query_map(params![product], |row| {
+                Ok(ProductScore {
+                    run_id: row.get(0)?,
+                    query: row.get(1)?,
+                    started_at: row.get(2)?,
+                    rank: row.get(3)?,
+                    score: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(history)
+    }
+
+    fn transaction<T>(&self, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let value = f(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    }
+}
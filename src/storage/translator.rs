@@ -0,0 +1,40 @@
+use crate::error::{Error, Result};
+use crate::storage::store::Store;
+use crate::translate::translator::{translate_misses, Translator};
+use futures::future::BoxFuture;
+
+/// Translator answering from the translations kept in a [`Store`] and
+/// falling back to `inner`, so that re-running the models on stored text
+/// doesn't translate it again.
+pub struct StoredTranslator {
+    store: Store,
+    inner: Box<dyn Translator>,
+}
+
+impl StoredTranslator {
+    pub fn new(store: Store, inner: Box<dyn Translator>) -> Self {
+        Self { store, inner }
+    }
+}
+
+impl Translator for StoredTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        source: &'a str,
+        target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(translate_misses(
+            self.inner.as_ref(),
+            texts,
+            source,
+            target,
+            |text| {
+                self.store
+                    .translation(text, source, target)
+                    .map_err(|x| Error::TranslationFailed(x.to_string()))
+            },
+            |_, _| {},
+        ))
+    }
+}
@@ -0,0 +1,252 @@
+use crate::recommend::product::ProductEntity;
+use crate::recommend::recommender::{Recommender, VideoSignals};
+use crate::report::model::{RunOptions, RunReport, ScriptChunk, TranscriptOutcome, VideoAnalysis};
+use crate::storage::schema;
+use crate::storage::store::Store;
+use crate::youtube_api::model::{
+    Comment, CommentSnippet, CommentThread, CommentThreadSnippet, Video,
+};
+use crate::youtube_transcript::parser::{Caption, Transcript, TranscriptCore};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn options() -> RunOptions {
+    RunOptions {
+        videos: 1,
+        comments: 10,
+        languages: vec!["ko".to_owned()],
+    }
+}
+
+fn transcript() -> Transcript {
+    Transcript {
+        transcripts: vec![
+            TranscriptCore {
+                text: "안녕하세요".to_owned(),
+                start: Duration::from_millis(0),
+                duration: Duration::from_millis(1500),
+                words: Vec::new(),
+            },
+            TranscriptCore {
+                text: "갤럭시 S23 배터리는".to_owned(),
+                start: Duration::from_millis(1500),
+                duration: Duration::from_millis(2250),
+                words: Vec::new(),
+            },
+        ],
+        caption: Some(Caption {
+            base_url: "https://www.youtube.com/api/timedtext".to_owned(),
+            lang_code: "ko".to_owned(),
+            kind: Some("asr".to_owned()),
+        }),
+    }
+}
+
+fn comment(id: &str, text: &str) -> CommentThread {
+    CommentThread {
+        id: id.to_owned(),
+        snippet: CommentThreadSnippet {
+            video_id: "abc".to_owned(),
+            top_level_comment: Comment {
+                id: id.to_owned(),
+                snippet: CommentSnippet {
+                    text_display: text.to_owned(),
+                    ..Default::default()
+                },
+            },
+            total_reply_count: 0,
+        },
+    }
+}
+
+pub(crate) fn analysis(product: &str) -> VideoAnalysis {
+    let mut signals = VideoSignals {
+        video_id: "abc".to_owned(),
+        title: "S23 리뷰".to_owned(),
+        sentiment: 0.5,
+        ..Default::default()
+    };
+    signals.add_mention(product, 2.0);
+
+    VideoAnalysis {
+        id: "abc".to_owned(),
+        title: "S23 리뷰".to_owned(),
+        channel_title: "채널".to_owned(),
+        published_at: "2024-01-01T00:00:00Z".to_owned(),
+        view_count: Some(1000),
+        like_count: Some(100),
+        transcript: transcript(),
+        transcript_outcome: TranscriptOutcome::Ok,
+        script: "안녕하세요 갤럭시 S23 배터리는".to_owned(),
+        translated_script: "Hello, the Galaxy S23 battery".to_owned(),
+        chunks: vec![ScriptChunk {
+            start: Duration::ZERO,
+            text: "안녕하세요 갤럭시 S23 배터리는".to_owned(),
+            translated_text: "Hello, the Galaxy S23 battery".to_owned(),
+        }],
+        answers: Vec::new(),
+        summary: vec!["배터리가 좋다".to_owned()],
+        translated_summary: vec!["The battery is good".to_owned()],
+        sentiments: Vec::new(),
+        aspects: Vec::new(),
+        script_keywords: Vec::new(),
+        comment_keywords: Vec::new(),
+        products: vec![ProductEntity {
+            name: product.to_owned(),
+            mentions: vec![product.to_owned()],
+            count: 2,
+            score: 2.0,
+        }],
+        signals,
+    }
+}
+
+/// starts, fills and finishes a run recommending `product`.
+fn save_run(store: &Store, query: &str, product: &str) -> i64 {
+    let run_id = store.start_run(Some(query), &options()).unwrap();
+    let video = analysis(product);
+    store.save_analysis(run_id, &video).unwrap();
+    let report = RunReport {
+        run_id: Some(run_id),
+        query: Some(query.to_owned()),
+        options: options(),
+        started_at: 0,
+        finished_at: 10,
+        recommendations: Recommender::default().rank(std::slice::from_ref(&video.signals)),
+        videos: vec![Arc::new(video)],
+        failures: Vec::new(),
+    };
+    store.finish_run(run_id, &report).unwrap();
+    run_id
+}
+
+#[test]
+fn storage_migrate_test() {
+    let store = Store::open_in_memory().unwrap();
+    assert_eq!(store.schema_version().unwrap(), schema::VERSION);
+}
+
+#[test]
+fn storage_video_test() {
+    let store = Store::open_in_memory().unwrap();
+    let mut video = Video {
+        id: "abc".to_owned(),
+        ..Default::default()
+    };
+    video.snippet.title = "S23 리뷰".to_owned();
+    video.statistics.view_count = Some(1000);
+    store.save_videos(std::slice::from_ref(&video)).unwrap();
+
+    // 다시 저장하면 덮어쓴다.
+    video.statistics.view_count = Some(2000);
+    store.save_videos(std::slice::from_ref(&video)).unwrap();
+
+    let stored = store.video("abc").unwrap().unwrap();
+    assert_eq!(stored.snippet.title, "S23 리뷰");
+    assert_eq!(stored.statistics.view_count, Some(2000));
+    assert!(store.video("missing").unwrap().is_none());
+}
+
+#[test]
+fn storage_transcript_test() {
+    let store = Store::open_in_memory().unwrap();
+    assert!(store.transcript("abc").unwrap().is_none());
+
+    store.save_transcript("abc", &transcript()).unwrap();
+    let stored = store.transcript("abc").unwrap().unwrap();
+    assert_eq!(stored.script(), transcript().script());
+    assert_eq!(stored.transcripts[1].start, Duration::from_millis(1500));
+    assert_eq!(stored.transcripts[1].duration, Duration::from_millis(2250));
+    let caption = stored.caption.unwrap();
+    assert_eq!(caption.lang_code, "ko");
+    assert_eq!(caption.kind.as_deref(), Some("asr"));
+}
+
+#[test]
+fn storage_comments_test() {
+    let store = Store::open_in_memory().unwrap();
+    let comments = [
+        comment("1", "좋아요"),
+        comment("2", "별로예요"),
+        comment("3", "최고"),
+    ];
+    let first = store.start_run(Some("갤럭시"), &options()).unwrap();
+    store.save_comments(first, "abc", &comments).unwrap();
+    assert_eq!(
+        store.comments(first, "abc", 2).unwrap(),
+        ["좋아요", "별로예요"]
+    );
+    assert!(store.comments(first, "other", 10).unwrap().is_empty());
+
+    // 다음 실행에서 댓글이 바뀌어도 이전 실행은 그때 분석한 댓글을 읽는다.
+    let second = store.start_run(Some("갤럭시"), &options()).unwrap();
+    let edited = [comment("3", "최고예요"), comment("4", "새 댓글")];
+    store.save_comments(second, "abc", &edited).unwrap();
+    assert_eq!(
+        store.comments(second, "abc", 10).unwrap(),
+        ["최고예요", "새 댓글"]
+    );
+    assert_eq!(
+        store.comments(first, "abc", 10).unwrap(),
+        ["좋아요", "별로예요", "최고예요"]
+    );
+
+    let rerun = store.start_run(Some("갤럭시"), &options()).unwrap();
+    store.copy_run_comments(first, rerun).unwrap();
+    assert_eq!(
+        store.comments(rerun, "abc", 10).unwrap(),
+        store.comments(first, "abc", 10).unwrap()
+    );
+    assert_eq!(
+        store.missing_channels(&["UCabc".to_owned()]).unwrap(),
+        ["UCabc"]
+    );
+}
+
+#[test]
+fn storage_run_test() {
+    let store = Store::open_in_memory().unwrap();
+    let run_id = save_run(&store, "갤럭시", "Galaxy S23");
+
+    let (query, options) = store.run(run_id).unwrap().unwrap();
+    assert_eq!(query.as_deref(), Some("갤럭시"));
+    assert_eq!(options.languages, ["ko"]);
+    assert_eq!(store.run_videos(run_id).unwrap(), ["abc"]);
+    assert!(store.run(run_id + 1).unwrap().is_none());
+
+    // 분석에서 나온 번역은 다시 실행할 때 쓴다.
+    assert_eq!(
+        store
+            .translation("안녕하세요 갤럭시 S23 배터리는", "KO", "EN")
+            .unwrap()
+            .as_deref(),
+        Some("Hello, the Galaxy S23 battery")
+    );
+    // 요약의 역번역은 저장하지 않는다.
+    assert!(store
+        .translation("The battery is good", "EN", "KO")
+        .unwrap()
+        .is_none());
+
+    let runs = store.runs(10).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].videos, 1);
+    assert_eq!(runs[0].finished_at, Some(10));
+    assert_eq!(runs[0].top_product.as_deref(), Some("Galaxy S23"));
+}
+
+#[test]
+fn storage_product_history_test() {
+    let store = Store::open_in_memory().unwrap();
+    let first = save_run(&store, "갤럭시", "Galaxy S23");
+    save_run(&store, "아이폰", "iPhone 15");
+    let third = save_run(&store, "갤럭시 울트라", "Galaxy S23");
+
+    let history = store.product_history("galaxy s23").unwrap();
+    assert_eq!(
+        history.iter().map(|x| x.run_id).collect::<Vec<_>>(),
+        [first, third]
+    );
+    assert!(history.iter().all(|x| x.rank == 1));
+    assert_eq!(store.runs(2).unwrap()[0].id, third);
+}
@@ -0,0 +1,161 @@
+use crate::youtube_transcript::config::{CaptionSource, Config};
+use crate::youtube_transcript::error::Error;
+use crate::youtube_transcript::format::CaptionFormat;
+use crate::youtube_transcript::parser::{TranscriptCore, TranscriptParser};
+use std::time::Duration;
+
+const XML: &str = r#"<?xml version="1.0" encoding="utf-8" ?><transcript>
+<text start="0.5" dur="2.1">안녕하세요 테크리뷰입니다</text>
+<text start="2.6" dur="3.4">배터리는 &amp; 카메라는</text>
+<text start="6" dur="1"></text>
+</transcript>"#;
+
+const SRV3: &str = r#"<?xml version="1.0" encoding="utf-8" ?><timedtext format="3">
+<head><ws id="1"/></head>
+<body>
+<p t="500" d="2100" w="1"><s ac="0">안녕하세요</s><s t="600" ac="0"> 테크리뷰입니다</s></p>
+<p t="2600" d="3400" w="1" a="1">
+</p>
+<p t="2600" d="3400">배터리는</p>
+</body>
+</timedtext>"#;
+
+const JSON3: &str = r#"{"wireMagic":"pb3","events":[
+{"tStartMs":0,"dDurationMs":6000,"id":1,"wpWinPosId":1,"wsWinStyleId":1},
+{"tStartMs":500,"dDurationMs":2100,"wWinId":1,"segs":[{"utf8":"안녕하세요","acAsrConf":0},{"utf8":" 테크리뷰입니다","tOffsetMs":600}]},
+{"tStartMs":2590,"dDurationMs":10,"wWinId":1,"aAppend":1,"segs":[{"utf8":"\n"}]},
+{"tStartMs":2600,"dDurationMs":3400,"wWinId":1,"segs":[{"utf8":"배터리는"}]}
+]}"#;
+
+const VTT: &str = "WEBVTT\nKind: captions\nLanguage: ko\n\n\
+00:00:00.500 --> 00:00:02.600 align:start position:0%\n\
+안녕하세요<00:00:01.100><c> 테크리뷰입니다</c>\n\n\
+00:00:02.600 --> 00:00:02.610 align:start position:0%\n\
+안녕하세요 테크리뷰입니다\n \n\n\
+02.610 --> 00:06.000\n\
+안녕하세요 테크리뷰입니다\n배터리는 &amp; 카메라는\n\n\
+06.000 --> 07.000\n\
+네 맞아요\n\n\
+07.000 --> 08.000\n\
+좋아요\n네 맞아요\n";
+
+const JSON_ERROR: &str = r#"{"error": {"code": 403, "message": "Forbidden"}}"#;
+
+fn segment(start_ms: u64, duration_ms: u64, text: &str) -> TranscriptCore {
+    TranscriptCore {
+        text: text.to_owned(),
+        start: Duration::from_millis(start_ms),
+        duration: Duration::from_millis(duration_ms),
+        words: Vec::new(),
+    }
+}
+
+/// segments rounded to milliseconds, as the xml and vtt times are floats.
+fn rounded(segments: Vec<TranscriptCore>) -> Vec<TranscriptCore> {
+    segments
+        .into_iter()
+        .map(|x| TranscriptCore {
+            start: Duration::from_millis(x.start.as_secs_f64().mul_add(1000.0, 0.5) as u64),
+            duration: Duration::from_millis(x.duration.as_secs_f64().mul_add(1000.0, 0.5) as u64),
+            ..x
+        })
+        .collect()
+}
+
+#[test]
+fn caption_format_detect_test() {
+    assert_eq!(CaptionFormat::detect(XML), Some(CaptionFormat::Xml));
+    assert_eq!(CaptionFormat::detect(SRV3), Some(CaptionFormat::Srv3));
+    assert_eq!(CaptionFormat::detect(JSON3), Some(CaptionFormat::Json3));
+    assert_eq!(
+        CaptionFormat::detect(&format!("\u{feff}{}", VTT)),
+        Some(CaptionFormat::Vtt)
+    );
+    // 자막이 아닌 json 은 빈 자막으로 읽지 않는다.
+    assert_eq!(CaptionFormat::detect(JSON_ERROR), None);
+    assert!(CaptionFormat::Json3.parse(JSON_ERROR).is_err());
+    assert!(CaptionFormat::Json3
+        .parse(r#"{"wireMagic": "pb3"}"#)
+        .unwrap()
+        .is_empty());
+    assert_eq!(CaptionFormat::detect("<html></html>"), None);
+    assert_eq!(CaptionFormat::detect(""), None);
+    assert!(TranscriptParser::parse("<html></html>").is_err());
+}
+
+#[test]
+fn caption_format_parse_test() {
+    let transcript = |text: &str| rounded(TranscriptParser::parse(text).unwrap().transcripts);
+
+    // 빈 자막은 빠진다.
+    assert_eq!(
+        transcript(XML),
+        [
+            segment(500, 2100, "안녕하세요 테크리뷰입니다"),
+            segment(2600, 3400, "배터리는 & 카메라는"),
+        ]
+    );
+    // 단어마다 나뉜 자막도 한 문장으로 읽고, 단어의 시작은 따로 둔다.
+    let mut words = segment(500, 2100, "안녕하세요 테크리뷰입니다");
+    words.words = vec![
+        (Duration::from_millis(500), "안녕하세요".to_owned()),
+        (Duration::from_millis(1100), "테크리뷰입니다".to_owned()),
+    ];
+    assert_eq!(transcript(SRV3), [words, segment(2600, 3400, "배터리는")]);
+    assert_eq!(
+        transcript(JSON3),
+        [
+            segment(500, 2100, "안녕하세요 테크리뷰입니다"),
+            segment(2600, 3400, "배터리는"),
+        ]
+    );
+    // 앞 큐에서 올라온 줄은 다시 넣지 않지만, 되풀이한 말은 남긴다.
+    assert_eq!(
+        transcript(VTT),
+        [
+            segment(500, 2100, "안녕하세요 테크리뷰입니다"),
+            segment(2610, 3390, "배터리는 & 카메라는"),
+            segment(6000, 1000, "네 맞아요"),
+            segment(7000, 1000, "좋아요\n네 맞아요"),
+        ]
+    );
+}
+
+#[test]
+fn caption_format_invalid_timing_test() {
+    let xml = |start: &str, dur: &str| {
+        format!(
+            r#"<transcript><text start="{}" dur="{}">안녕하세요</text></transcript>"#,
+            start, dur
+        )
+    };
+    let vtt = |timing: &str| format!("WEBVTT\n\n{}\n안녕하세요\n", timing);
+
+    // 잘못된 시간 하나가 실행 전체를 멈추지 않고 읽기 오류가 된다.
+    for text in [
+        xml("-0.5", "1"),
+        xml("0", "NaN"),
+        xml("inf", "1"),
+        vtt("-00:01.000 --> 00:02.000"),
+        vtt("00:01.000 --> NaN"),
+    ] {
+        assert!(
+            matches!(TranscriptParser::parse(&text), Err(Error::ParseError(_))),
+            "{}",
+            text
+        );
+    }
+    assert!(TranscriptParser::parse(&vtt("00:01.000 --> 00:02.000")).is_ok());
+}
+
+#[test]
+fn transcript_source_env_test() {
+    // TRANSCRIPT_SOURCE 는 이 테스트만 읽는다.
+    std::env::set_var("TRANSCRIPT_SOURCE", "HTML");
+    assert_eq!(Config::from_env().unwrap().source, CaptionSource::Html);
+    // 잘못된 값은 기본값으로 넘어가지 않는다.
+    std::env::set_var("TRANSCRIPT_SOURCE", "innertub");
+    assert!(Config::from_env().is_err());
+    std::env::remove_var("TRANSCRIPT_SOURCE");
+    assert_eq!(Config::from_env().unwrap().source, CaptionSource::InnerTube);
+}
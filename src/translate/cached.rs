@@ -0,0 +1,49 @@
+use crate::cache::{self, Cache};
+use crate::error::Result;
+use crate::translate::translator::{translate_misses, Translator};
+use futures::future::BoxFuture;
+
+/// Translator keeping every translation of `inner` in a [`Cache`], keyed by
+/// the language pair and the text, so that only unseen texts are sent.
+pub struct CachedTranslator {
+    inner: Box<dyn Translator>,
+    cache: Cache,
+    /// namespace of the entries, translations of different engines differ
+    namespace: String,
+}
+
+impl CachedTranslator {
+    pub fn new(inner: Box<dyn Translator>, cache: Cache, engine: &str) -> Self {
+        Self {
+            inner,
+            cache,
+            namespace: format!("translate-{}", engine),
+        }
+    }
+}
+
+impl Translator for CachedTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        source: &'a str,
+        target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(async move {
+            let key = |text: &str| Cache::key(&[source, target, text]);
+            translate_misses(
+                self.inner.as_ref(),
+                texts,
+                source,
+                target,
+                |text| {
+                    Ok(self
+                        .cache
+                        .get(&self.namespace, &key(text), cache::TTL_TRANSLATION))
+                },
+                |text, translation| self.cache.put(&self.namespace, &key(text), &translation),
+            )
+            .await
+        })
+    }
+}
@@ -0,0 +1,129 @@
+use crate::cache::Cache;
+use crate::error::{Error, Result};
+use crate::translate::cached::CachedTranslator;
+use crate::translate::deepl::DeepLTranslator;
+use crate::translate::libre::LibreTranslator;
+use crate::translate::marian::MarianTranslator;
+use crate::translate::translator::{IdentityTranslator, Translator};
+use rust_bert::pipelines::translation::Language;
+use std::str::FromStr;
+
+/// Translation backends that can be picked with `TRANSLATOR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    #[default]
+    DeepLFree,
+    DeepLPro,
+    Libre,
+    Marian,
+    Identity,
+}
+
+impl FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "deepl" | "deepl-free" => Ok(Backend::DeepLFree),
+            "deepl-pro" => Ok(Backend::DeepLPro),
+            "libre" | "libretranslate" => Ok(Backend::Libre),
+            "marian" => Ok(Backend::Marian),
+            "identity" | "none" => Ok(Backend::Identity),
+            _ => Err(Error::TranslationFailed(format!(
+                "unknown translator: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Backend {
+    /// engine behind the backend, both DeepL plans translating the same way.
+    pub fn engine(self) -> &'static str {
+        match self {
+            Backend::DeepLFree | Backend::DeepLPro => "deepl",
+            Backend::Libre => "libre",
+            Backend::Marian => "marian",
+            Backend::Identity => "identity",
+        }
+    }
+}
+
+/// configuration of the translation backend.
+///
+/// | variable | used by |
+/// | --- | --- |
+/// | `TRANSLATOR` | `deepl-free` (default), `deepl-pro`, `libre`, `marian` or `identity` |
+/// | `DEEPL_API_KEY` | `deepl-free`, `deepl-pro` |
+/// | `DEEPL_API_URL` | `deepl-free`, `deepl-pro`, replaces the url of the plan |
+/// | `LIBRETRANSLATE_URL` | `libre`, defaults to `http://localhost:5000` |
+/// | `LIBRETRANSLATE_API_KEY` | `libre`, optional |
+#[derive(Debug, Clone, Default)]
+pub struct TranslatorConfig {
+    pub backend: Backend,
+    pub deepl_api_key: Option<String>,
+    /// translate endpoint, Ex: "https://api-free.deepl.com/v2/translate"
+    pub deepl_url: Option<String>,
+    pub libre_url: Option<String>,
+    pub libre_api_key: Option<String>,
+}
+
+impl TranslatorConfig {
+    /// reads the configuration from the environment.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            backend: match std::env::var("TRANSLATOR") {
+                Ok(backend) => backend.parse()?,
+                Err(_) => Backend::default(),
+            },
+            deepl_api_key: std::env::var("DEEPL_API_KEY").ok(),
+            deepl_url: std::env::var("DEEPL_API_URL").ok(),
+            libre_url: std::env::var("LIBRETRANSLATE_URL").ok(),
+            libre_api_key: std::env::var("LIBRETRANSLATE_API_KEY").ok(),
+        })
+    }
+
+    /// creates the configured backend, waiting for the model of `marian` to
+    /// be loaded.
+    pub async fn build(&self) -> Result<Box<dyn Translator>> {
+        let deepl_api_key = || {
+            self.deepl_api_key
+                .clone()
+                .ok_or_else(|| Error::TranslationFailed("DEEPL_API_KEY is not set".to_owned()))
+        };
+
+        Ok(match self.backend {
+            Backend::DeepLFree | Backend::DeepLPro => Box::new(match &self.deepl_url {
+                Some(url) => DeepLTranslator::with_url(deepl_api_key()?, url),
+                None if self.backend == Backend::DeepLPro => DeepLTranslator::pro(deepl_api_key()?),
+                None => DeepLTranslator::free(deepl_api_key()?),
+            }),
+            Backend::Libre => Box::new(LibreTranslator::new(
+                self.libre_url
+                    .clone()
+                    .unwrap_or_else(|| "http://localhost:5000".to_owned()),
+                self.libre_api_key.clone(),
+            )),
+            Backend::Marian => {
+                // 모델 스레드는 번역기가 모두 사라지면 끝나므로 기다리지 않는다.
+                let (_handle, translator) =
+                    MarianTranslator::spawn(vec![Language::Korean, Language::English]).await?;
+                Box::new(translator)
+            }
+            Backend::Identity => Box::new(IdentityTranslator),
+        })
+    }
+
+    /// creates the configured backend, keeping its translations in `cache`.
+    pub async fn build_cached(&self, cache: Cache) -> Result<Box<dyn Translator>> {
+        let translator = self.build().await?;
+        if self.backend == Backend::Identity || !cache.is_enabled() {
+            return Ok(translator);
+        }
+        Ok(Box::new(CachedTranslator::new(
+            translator,
+            cache,
+            self.backend.engine(),
+        )))
+    }
+}
@@ -0,0 +1,122 @@
+use crate::error::{Error, Result};
+use crate::translate::translator::{translate_in_batches, Translator};
+use futures::future::BoxFuture;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const FREE_URL: &str = "https://api-free.deepl.com/v2/translate";
+const PRO_URL: &str = "https://api.deepl.com/v2/translate";
+// 429 응답 재시도 횟수
+const MAX_RETRIES: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    text: &'a [&'a str],
+    source_lang: &'a str,
+    target_lang: &'a str,
+}
+
+#[derive(Deserialize)]
+struct TranslateResponse {
+    translations: Vec<Translation>,
+}
+
+#[derive(Deserialize)]
+struct Translation {
+    text: String,
+}
+
+/// DeepL client that sends texts in batches, keeping their order.
+#[derive(Debug, Clone)]
+pub struct DeepLTranslator {
+    client: Client,
+    api_key: String,
+    url: String,
+}
+
+impl DeepLTranslator {
+    /// creates [`DeepLTranslator`] for the DeepL API Free with the given api key.
+    pub fn free(api_key: impl Into<String>) -> Self {
+        Self::with_url(api_key, FREE_URL)
+    }
+
+    /// creates [`DeepLTranslator`] for the DeepL API Pro with the given api key.
+    pub fn pro(api_key: impl Into<String>) -> Self {
+        Self::with_url(api_key, PRO_URL)
+    }
+
+    /// creates [`DeepLTranslator`] sending requests to `url`.
+    pub fn with_url(api_key: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: api_key.into(),
+            url: url.into(),
+        }
+    }
+
+    /// sends one batch, retrying with exponential backoff while DeepL answers 429.
+    async fn request(&self, texts: Vec<&str>, source: &str, target: &str) -> Result<Vec<String>> {
+        let body = TranslateRequest {
+            text: &texts,
+            source_lang: source,
+            target_lang: target,
+        };
+        let mut backoff = INITIAL_BACKOFF;
+        let mut retries = 0;
+
+        loop {
+            let response = self
+                .client
+                .post(&self.url)
+                .header("Authorization", format!("DeepL-Auth-Key {}", self.api_key))
+                .json(&body)
+                .send()
+                .await?;
+
+            let status = response.status();
+            if status == StatusCode::TOO_MANY_REQUESTS && retries < MAX_RETRIES {
+                let wait = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|x| x.to_str().ok())
+                    .and_then(|x| x.parse().ok())
+                    .map(Duration::from_secs)
+                    .unwrap_or(backoff);
+                tokio::time::sleep(wait).await;
+                backoff *= 2;
+                retries += 1;
+                continue;
+            }
+            if !status.is_success() {
+                let body = response.text().await?;
+                return Err(Error::TranslationFailed(format!("{}: {}", status, body)));
+            }
+
+            let response = response.json::<TranslateResponse>().await?;
+            if response.translations.len() != texts.len() {
+                return Err(Error::TranslationFailed(format!(
+                    "sent {} texts, got {} translations",
+                    texts.len(),
+                    response.translations.len()
+                )));
+            }
+            return Ok(response.translations.into_iter().map(|x| x.text).collect());
+        }
+    }
+}
+
+impl Translator for DeepLTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        source: &'a str,
+        target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(translate_in_batches(texts, move |batch| {
+            Box::pin(self.request(batch, source, target))
+        }))
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::{Error, Result};
+use crate::translate::translator::{translate_in_batches, Translator};
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a [&'a str],
+    source: String,
+    target: String,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct TranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: Vec<String>,
+}
+
+/// Client for a LibreTranslate compatible server, Ex: a local
+/// `libretranslate --host 127.0.0.1 --port 5000`.
+#[derive(Debug, Clone)]
+pub struct LibreTranslator {
+    client: Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+impl LibreTranslator {
+    /// creates [`LibreTranslator`] for the server at `url`, Ex: "http://localhost:5000".
+    pub fn new(url: impl Into<String>, api_key: Option<String>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into().trim_end_matches('/').to_owned(),
+            api_key,
+        }
+    }
+
+    async fn request(&self, texts: Vec<&str>, source: &str, target: &str) -> Result<Vec<String>> {
+        let body = TranslateRequest {
+            q: &texts,
+            // LibreTranslate uses lowercase ISO 639-1 codes
+            source: source.to_lowercase(),
+            target: target.to_lowercase(),
+            format: "text",
+            api_key: self.api_key.as_deref(),
+        };
+        let response = self
+            .client
+            .post(format!("{}/translate", self.url))
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await?;
+            return Err(Error::TranslationFailed(format!("{}: {}", status, body)));
+        }
+
+        let response = response.json::<TranslateResponse>().await?;
+        if response.translated_text.len() != texts.len() {
+            return Err(Error::TranslationFailed(format!(
+                "sent {} texts, got {} translations",
+                texts.len(),
+                response.translated_text.len()
+            )));
+        }
+        Ok(response.translated_text)
+    }
+}
+
+impl Translator for LibreTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        source: &'a str,
+        target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(translate_in_batches(texts, move |batch| {
+            Box::pin(self.request(batch, source, target))
+        }))
+    }
+}
@@ -0,0 +1,81 @@
+use crate::classifier::model_worker::{ModelWorker, Pipeline};
+use crate::error::{Error, Result};
+use crate::translate::translator::Translator;
+use futures::future::BoxFuture;
+use rust_bert::pipelines::translation::{Language, TranslationModel, TranslationModelBuilder};
+use tokio::task::JoinHandle;
+
+impl Pipeline for TranslationModel {
+    /// languages the model has to translate between
+    type Config = Vec<Language>;
+    type Input = (Vec<String>, Language, Language);
+    type Output = Vec<String>;
+
+    fn load(languages: Self::Config) -> anyhow::Result<Self> {
+        Ok(TranslationModelBuilder::new()
+            .with_source_languages(languages.clone())
+            .with_target_languages(languages)
+            .create_model()?)
+    }
+
+    fn run(&self, (texts, source, target): Self::Input) -> anyhow::Result<Self::Output> {
+        Ok(self.translate(&texts, source, target)?)
+    }
+}
+
+/// Offline backend running a rust-bert translation model (Marian, or M2M100
+/// for language pairs Marian has no model for) on a separate thread.
+#[derive(Debug, Clone)]
+pub struct MarianTranslator {
+    worker: ModelWorker<TranslationModel>,
+}
+
+impl MarianTranslator {
+    /// Spawn a model translating between `languages` on a separate thread and
+    /// return a translator instance to interact with it once the model is
+    /// loaded, so that a model that can't be loaded fails here with its
+    /// [`Error::ModelLoadFailed`] rather than on every translation
+    pub async fn spawn(
+        languages: Vec<Language>,
+    ) -> Result<(JoinHandle<anyhow::Result<()>>, MarianTranslator)> {
+        let (handle, worker) = ModelWorker::spawn_loaded(languages).await.map_err(|e| {
+            e.downcast::<Error>()
+                .unwrap_or_else(|e| Error::ModelLoadFailed(e.to_string()))
+        })?;
+        Ok((handle, MarianTranslator { worker }))
+    }
+}
+
+/// maps a DeepL language code to the rust-bert language.
+fn language(code: &str) -> Result<Language> {
+    match code.to_uppercase().as_str() {
+        "KO" => Ok(Language::Korean),
+        "EN" | "EN-US" | "EN-GB" => Ok(Language::English),
+        "JA" => Ok(Language::Japanese),
+        "ZH" => Ok(Language::ChineseMandarin),
+        "DE" => Ok(Language::German),
+        "FR" => Ok(Language::French),
+        "ES" => Ok(Language::Spanish),
+        _ => Err(Error::TranslationFailed(format!(
+            "unsupported language: {}",
+            code
+        ))),
+    }
+}
+
+impl Translator for MarianTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        source: &'a str,
+        target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(async move {
+            let input = (texts.to_vec(), language(source)?, language(target)?);
+            self.worker
+                .request(input)
+                .await
+                .map_err(|e| Error::TranslationFailed(e.to_string()))
+        })
+    }
+}
@@ -0,0 +1,6 @@
+pub mod cached;
+pub mod config;
+pub mod deepl;
+pub mod libre;
+pub mod marian;
+pub mod translator;
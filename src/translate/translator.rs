@@ -0,0 +1,140 @@
+use crate::error::{Error, Result};
+use futures::future::BoxFuture;
+use std::ops::Range;
+
+// 요청 하나에 담을 수 있는 최대 텍스트 수
+const MAX_TEXTS: usize = 50;
+// 요청 본문 최대 크기 (128 KiB)
+const MAX_PAYLOAD: usize = 128 * 1024;
+// 텍스트마다 붙는 json 따옴표, 쉼표 등의 크기
+const TEXT_OVERHEAD: usize = 8;
+
+/// Translation backend. Languages are given as DeepL codes, Ex: "KO", "EN".
+pub trait Translator: Send + Sync {
+    /// translates the texts, `result[i]` being the translation of `texts[i]`.
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        source: &'a str,
+        target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>>;
+
+    /// translates a single text.
+    fn translate_one<'a>(
+        &'a self,
+        text: &'a str,
+        source: &'a str,
+        target: &'a str,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let mut translations = self.translate(&[text.to_owned()], source, target).await?;
+            Ok(translations.remove(0))
+        })
+    }
+}
+
+/// Backend that hands the texts back untouched, for content that is already
+/// in the target language.
+#[derive(Debug, Clone, Default)]
+pub struct IdentityTranslator;
+
+impl Translator for IdentityTranslator {
+    fn translate<'a>(
+        &'a self,
+        texts: &'a [String],
+        _source: &'a str,
+        _target: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>>> {
+        Box::pin(async move { Ok(texts.to_vec()) })
+    }
+}
+
+/// splits texts of the given sizes into consecutive batches of at most
+/// [`MAX_TEXTS`] texts and [`MAX_PAYLOAD`] bytes. A text larger than the
+/// payload limit gets a batch of its own.
+pub(crate) fn batches(sizes: &[usize]) -> Vec<Range<usize>> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut payload = 0;
+
+    for (i, size) in sizes.iter().enumerate() {
+        let size = size + TEXT_OVERHEAD;
+        if i > start && (i - start == MAX_TEXTS || payload + size > MAX_PAYLOAD) {
+            batches.push(start..i);
+            start = i;
+            payload = 0;
+        }
+        payload += size;
+    }
+    if start < sizes.len() {
+        batches.push(start..sizes.len());
+    }
+
+    batches
+}
+
+/// sends the non-empty texts through `request` in [`batches`] and puts the
+/// translations back in place. Empty texts are not sent and come back empty.
+pub(crate) async fn translate_in_batches<'a, F>(
+    texts: &'a [String],
+    mut request: F,
+) -> Result<Vec<String>>
+where
+    F: FnMut(Vec<&'a str>) -> BoxFuture<'a, Result<Vec<String>>>,
+{
+    let mut translations = vec![String::new(); texts.len()];
+    let indices: Vec<usize> = (0..texts.len())
+        .filter(|&i| !texts[i].trim().is_empty())
+        .collect();
+    let sizes: Vec<usize> = indices.iter().map(|&i| texts[i].len()).collect();
+
+    for batch in batches(&sizes) {
+        let batch = &indices[batch];
+        let batch_texts: Vec<&str> = batch.iter().map(|&i| texts[i].as_str()).collect();
+        let batch_translations = request(batch_texts).await?;
+
+        for (&i, translation) in batch.iter().zip(batch_translations) {
+            translations[i] = translation;
+        }
+    }
+
+    Ok(translations)
+}
+
+/// translates with `inner` only the texts `lookup` has no translation for,
+/// handing every new translation to `keep`. Fails if `inner` answers fewer
+/// or more translations than it was sent.
+pub(crate) async fn translate_misses(
+    inner: &dyn Translator,
+    texts: &[String],
+    source: &str,
+    target: &str,
+    lookup: impl Fn(&str) -> Result<Option<String>>,
+    keep: impl Fn(&str, &str),
+) -> Result<Vec<String>> {
+    let mut translations = texts
+        .iter()
+        .map(|text| lookup(text))
+        .collect::<Result<Vec<Option<String>>>>()?;
+
+    let misses: Vec<usize> = (0..texts.len())
+        .filter(|&i| translations[i].is_none())
+        .collect();
+    if !misses.is_empty() {
+        let missing: Vec<String> = misses.iter().map(|&i| texts[i].clone()).collect();
+        let translated = inner.translate(&missing, source, target).await?;
+        if translated.len() != misses.len() {
+            return Err(Error::TranslationFailed(format!(
+                "{} translations for {} texts",
+                translated.len(),
+                misses.len()
+            )));
+        }
+        for (i, translation) in misses.into_iter().zip(translated) {
+            keep(&texts[i], &translation);
+            translations[i] = Some(translation);
+        }
+    }
+
+    Ok(translations.into_iter().flatten().collect())
+}
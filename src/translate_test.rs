@@ -0,0 +1,12 @@
+use crate::translate::translator::batches;
+
+#[test]
+fn translator_batches_test() {
+    assert!(batches(&[]).is_empty());
+
+    let sizes = vec![10; 120];
+    assert_eq!(batches(&sizes), vec![0..50, 50..100, 100..120]);
+
+    let sizes = vec![100 * 1024, 10, 60 * 1024, 200 * 1024, 10];
+    assert_eq!(batches(&sizes), vec![0..2, 2..3, 3..4, 4..5]);
+}
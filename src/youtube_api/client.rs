@@ -0,0 +1,260 @@
+use crate::cache::{self, Cache};
+use crate::error::{Error, Result};
+use crate::rate_limit::RateLimiter;
+use crate::youtube_api::model::{Channel, CommentThread, Page, SearchResult, Video};
+use crate::youtube_transcript::youtube;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+const BASE_URL: &str = "https://www.googleapis.com/youtube/v3";
+// search.list 가 한 번에 돌려주는 최대 결과 수
+const MAX_PAGE_SIZE_SEARCH: usize = 50;
+// commentThreads.list 가 한 번에 돌려주는 최대 결과 수
+const MAX_PAGE_SIZE_COMMENT: usize = 100;
+// videos.list, channels.list 의 id 파라미터 최대 개수
+const MAX_IDS: usize = 50;
+// 동시에 가져오는 비디오 수
+pub const CONCURRENCY: usize = 4;
+
+/// Client for the YouTube Data API v3 that owns the http client and the api key.
+#[derive(Debug, Clone)]
+pub struct YoutubeApiClient {
+    client: Client,
+    api_key: String,
+    cache: Cache,
+    /// Ex: "https://www.googleapis.com/youtube/v3"
+    base_url: String,
+    /// site of the watch pages, Ex: "https://www.youtube.com"
+    web_url: String,
+    /// shared with the fetches of the watch pages and the captions
+    rate_limiter: RateLimiter,
+    /// videos fetched at once in a run
+    concurrency: usize,
+}
+
+impl YoutubeApiClient {
+    /// creates [`YoutubeApiClient`] with the given api key.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            api_key: api_key.into(),
+            cache: Cache::disabled(),
+            base_url: BASE_URL.to_owned(),
+            web_url: youtube::BASE_URL.to_owned(),
+            rate_limiter: RateLimiter::default(),
+            concurrency: CONCURRENCY,
+        }
+    }
+
+    /// sends the api requests to `base_url` instead of googleapis.com.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// fetches the watch pages and the captions from `web_url` instead of youtube.com.
+    pub fn with_web_url(mut self, web_url: impl Into<String>) -> Self {
+        self.web_url = web_url.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// keeps the responses in `cache`, see [`cache`] for how long.
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// spaces out the requests with `rate_limiter` instead of the default limits.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// fetches up to `concurrency` videos at once in a run, at least one.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// creates [`YoutubeApiClient`] with the api key in `YOUTUBE_API_KEY`.
+    /// `YOUTUBE_API_URL` and `YOUTUBE_URL` replace the default urls.
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self::new(std::env::var("YOUTUBE_API_KEY")?).with_env_urls())
+    }
+
+    /// replaces the default urls with `YOUTUBE_API_URL` and `YOUTUBE_URL`, if set.
+    pub fn with_env_urls(self) -> Self {
+        let client = self.with_web_url(youtube::base_url_from_env());
+        match std::env::var("YOUTUBE_API_URL") {
+            Ok(base_url) => client.with_base_url(base_url),
+            Err(_) => client,
+        }
+    }
+
+    /// cache of the responses, also used for the other YouTube fetches of a run.
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// site of the watch pages, used for the transcripts of a run.
+    pub fn web_url(&self) -> &str {
+        &self.web_url
+    }
+
+    /// rate limits, also used for the other YouTube fetches of a run.
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// searches videos matching `query`, up to `max_results` items.
+    pub async fn search(&self, query: &str, max_results: usize) -> Result<Vec<SearchResult>> {
+        let params = [
+            ("part", "snippet".to_owned()),
+            ("q", query.to_owned()),
+            ("type", "video".to_owned()),
+        ];
+        self.list("search", &params, MAX_PAGE_SIZE_SEARCH, max_results)
+            .await
+    }
+
+    /// fetches comment threads of a video, up to `max_results` items.
+    pub async fn comment_threads(
+        &self,
+        video_id: &str,
+        max_results: usize,
+    ) -> Result<Vec<CommentThread>> {
+        let params = [
+            ("part", "snippet".to_owned()),
+            ("videoId", video_id.to_owned()),
+        ];
+        self.list(
+            "commentThreads",
+            &params,
+            MAX_PAGE_SIZE_COMMENT,
+            max_results,
+        )
+        .await
+    }
+
+    /// fetches snippet and statistics of the given videos.
+    pub async fn videos(&self, ids: &[String]) -> Result<Vec<Video>> {
+        self.list_by_ids("videos", ids).await
+    }
+
+    /// fetches snippet and statistics of the given channels.
+    pub async fn channels(&self, ids: &[String]) -> Result<Vec<Channel>> {
+        self.list_by_ids("channels", ids).await
+    }
+
+    async fn list_by_ids<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        ids: &[String],
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+
+        for chunk in ids.chunks(MAX_IDS) {
+            let params = [
+                ("part", "snippet,statistics".to_owned()),
+                ("id", chunk.join(",")),
+            ];
+            let page = self.page::<T>(resource, &params, MAX_IDS, None).await?;
+            items.extend(page.items);
+        }
+
+        Ok(items)
+    }
+
+    /// follows `nextPageToken` until `max_results` items are collected.
+    async fn list<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        params: &[(&str, String)],
+        page_size: usize,
+        max_results: usize,
+    ) -> Result<Vec<T>> {
+        let mut page_token = None;
+        let mut items = Vec::new();
+
+        while items.len() < max_results {
+            let page_size = page_size.min(max_results - items.len());
+            let page = self
+                .page::<T>(resource, params, page_size, page_token.as_deref())
+                .await?;
+            items.extend(page.items);
+
+            match page.next_page_token {
+                Some(next_page_token) if items.len() < max_results => {
+                    page_token = Some(next_page_token)
+                }
+                _ => break,
+            }
+        }
+
+        items.truncate(max_results);
+        Ok(items)
+    }
+
+    async fn page<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        params: &[(&str, String)],
+        page_size: usize,
+        page_token: Option<&str>,
+    ) -> Result<Page<T>> {
+        let page_size = page_size.to_string();
+        let page_token = page_token.unwrap_or_default();
+        // api 키를 뺀 엔드포인트와 파라미터가 캐시 키가 된다.
+        let mut key = vec![resource, page_size.as_str(), page_token];
+        key.extend(
+            params
+                .iter()
+                .flat_map(|(name, value)| [*name, value.as_str()]),
+        );
+
+        let key = Cache::key(&key);
+        // 읽지 못하는 응답은 캐시하지 않고, 이미 캐시된 것은 다시 가져온다.
+        if let Some(body) = self.cache.get::<String>(resource, &key, ttl(resource)) {
+            if let Ok(page) = serde_json::from_str(&body) {
+                return Ok(page);
+            }
+        }
+
+        let url = format!("{}/{}", self.base_url, resource);
+        self.rate_limiter.wait(&url).await;
+        let response = self
+            .client
+            .get(url)
+            .query(params)
+            .query(&[
+                ("key", self.api_key.as_str()),
+                ("maxResults", page_size.as_str()),
+                ("pageToken", page_token),
+            ])
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(Error::from_youtube_response(status, body));
+        }
+        let page = serde_json::from_str::<Page<T>>(&body)?;
+        self.cache.put(resource, &key, &body);
+        Ok(page)
+    }
+}
+
+fn ttl(resource: &str) -> Duration {
+    match resource {
+        "search" => cache::TTL_SEARCH,
+        "videos" => cache::TTL_VIDEOS,
+        "channels" => cache::TTL_CHANNELS,
+        _ => cache::TTL_COMMENTS,
+    }
+}
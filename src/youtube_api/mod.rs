@@ -0,0 +1,3 @@
+// https://developers.google.com/youtube/v3/docs
+pub mod client;
+pub mod model;
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Deserializes counters that the Data API encodes as strings (`"viewCount": "1234"`).
+fn u64_from_str<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Count {
+        Number(u64),
+        Text(String),
+    }
+
+    Ok(match Option::<Count>::deserialize(deserializer)? {
+        Some(Count::Number(x)) => Some(x),
+        Some(Count::Text(x)) => x.parse().ok(),
+        None => None,
+    })
+}
+
+/// One page of a list response.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Page<T> {
+    #[serde(default = "Vec::new")]
+    pub items: Vec<T>,
+    pub next_page_token: Option<String>,
+}
+
+/// Identifier of a `search` result.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceId {
+    #[serde(default)]
+    pub kind: String,
+    pub video_id: Option<String>,
+    pub channel_id: Option<String>,
+}
+
+/// Snippet shared by `search` results and `videos`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoSnippet {
+    pub published_at: String,
+    pub channel_id: String,
+    pub channel_title: String,
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+}
+
+/// Item of `search.list`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SearchResult {
+    pub id: ResourceId,
+    pub snippet: VideoSnippet,
+}
+
+impl SearchResult {
+    /// video id of the result, `None` for channel or playlist results.
+    pub fn video_id(&self) -> Option<&str> {
+        self.id.video_id.as_deref()
+    }
+}
+
+/// `statistics` part of a video.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoStatistics {
+    #[serde(deserialize_with = "u64_from_str")]
+    pub view_count: Option<u64>,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub like_count: Option<u64>,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub comment_count: Option<u64>,
+}
+
+/// Item of `videos.list`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Video {
+    pub id: String,
+    pub snippet: VideoSnippet,
+    pub statistics: VideoStatistics,
+}
+
+/// Snippet of a single comment.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommentSnippet {
+    pub video_id: String,
+    pub text_display: String,
+    pub text_original: String,
+    pub author_display_name: String,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub like_count: Option<u64>,
+    pub published_at: String,
+}
+
+/// Comment resource, used as the top level comment of a [`CommentThread`].
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Comment {
+    pub id: String,
+    pub snippet: CommentSnippet,
+}
+
+/// Snippet of a comment thread.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CommentThreadSnippet {
+    pub video_id: String,
+    pub top_level_comment: Comment,
+    pub total_reply_count: u64,
+}
+
+/// Item of `commentThreads.list`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CommentThread {
+    pub id: String,
+    pub snippet: CommentThreadSnippet,
+}
+
+impl CommentThread {
+    /// displayed text of the top level comment.
+    pub fn text(&self) -> &str {
+        &self.snippet.top_level_comment.snippet.text_display
+    }
+}
+
+/// Snippet of a channel.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelSnippet {
+    pub title: String,
+    pub description: String,
+    pub custom_url: Option<String>,
+}
+
+/// `statistics` part of a channel.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChannelStatistics {
+    #[serde(deserialize_with = "u64_from_str")]
+    pub subscriber_count: Option<u64>,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub video_count: Option<u64>,
+    #[serde(deserialize_with = "u64_from_str")]
+    pub view_count: Option<u64>,
+}
+
+/// Item of `channels.list`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Channel {
+    pub id: String,
+    pub snippet: ChannelSnippet,
+    pub statistics: ChannelStatistics,
+}
@@ -1,26 +1,105 @@
-use once_cell::sync::Lazy;
+use crate::youtube_transcript::error::Error;
+use once_cell::sync::OnceCell;
+use std::str::FromStr;
 
 pub struct HTMLParserConfig {
     pub from: &'static str,
     pub to: &'static str,
+    /// anchor of the playability status, present on every watch page
+    pub playability: &'static str,
 }
 impl Default for HTMLParserConfig {
     fn default() -> Self {
         Self {
             from: "playerCaptionsTracklistRenderer\":",
             to: "},\"videoDetails\"",
+            playability: "\"playabilityStatus\":{\"status\":\"",
         }
     }
 }
-/// configuration that contains anchor points for identifying captions from youtube's html webpage.
+
+/// client the `youtubei/v1/player` api is called as.
+pub struct InnerTubeConfig {
+    pub client_name: &'static str,
+    pub client_version: &'static str,
+}
+impl Default for InnerTubeConfig {
+    fn default() -> Self {
+        Self {
+            client_name: "ANDROID",
+            client_version: "20.10.38",
+        }
+    }
+}
+
+/// where the caption tracks of a video are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptionSource {
+    /// the `youtubei/v1/player` json api, the watch page when it fails
+    #[default]
+    InnerTube,
+    /// the player response embedded in the watch page html
+    Html,
+}
+impl FromStr for CaptionSource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "innertube" => Ok(CaptionSource::InnerTube),
+            "html" => Ok(CaptionSource::Html),
+            _ => Err(Error::ParseError(format!(
+                "unknown transcript source: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// configuration that contains anchor points for identifying captions from youtube's html webpage,
+/// the client of the player api and the source of the captions.
 pub struct Config {
     pub(crate) parser: HTMLParserConfig,
+    pub(crate) innertube: InnerTubeConfig,
+    pub(crate) source: CaptionSource,
 }
 impl Default for Config {
     fn default() -> Self {
         Self {
             parser: HTMLParserConfig::default(),
+            innertube: InnerTubeConfig::default(),
+            source: CaptionSource::default(),
         }
     }
 }
-pub static CONFIG_VAL: Lazy<Config> = Lazy::new(|| Config::default());
+impl Config {
+    /// lists the caption tracks from `source`.
+    pub fn with_source(mut self, source: CaptionSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// [`Config`] with the source in `TRANSCRIPT_SOURCE`, "innertube" or "html",
+    /// the default source when it is not set. Unknown values are rejected.
+    pub fn from_env() -> Result<Self, Error> {
+        let source = match std::env::var("TRANSCRIPT_SOURCE") {
+            Ok(source) => source.parse()?,
+            Err(_) => CaptionSource::default(),
+        };
+        Ok(Self::default().with_source(source))
+    }
+}
+
+static CONFIG_VAL: OnceCell<Config> = OnceCell::new();
+
+/// makes `config` the [`Config`] of the loaders built afterwards with
+/// [`YoutubeBuilder::default`](crate::youtube_transcript::youtube::YoutubeBuilder::default),
+/// Ex: [`Config::from_env`]. Only the first call counts.
+pub fn init(config: Config) {
+    let _ = CONFIG_VAL.set(config);
+}
+
+/// the [`Config`] given to [`init`], default values if it was not called.
+pub fn current() -> &'static Config {
+    CONFIG_VAL.get_or_init(Config::default)
+}
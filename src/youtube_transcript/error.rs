@@ -2,6 +2,10 @@ use std::{error::Error as StdError, fmt::Display};
 #[derive(Debug)]
 pub enum Error {
     ParseError(String),
+    RequestError(reqwest::Error),
+    /// the page is not the watch page of a playable video, Ex: a consent page,
+    /// a removed or private video
+    Unavailable(String),
 }
 
 impl Display for Error {
@@ -11,3 +15,27 @@ impl Display for Error {
 }
 
 impl StdError for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(value: reqwest::Error) -> Self {
+        Error::RequestError(value)
+    }
+}
+
+impl From<roxmltree::Error> for Error {
+    fn from(value: roxmltree::Error) -> Self {
+        Error::ParseError(format!("{}", value))
+    }
+}
+
+impl From<std::num::ParseFloatError> for Error {
+    fn from(value: std::num::ParseFloatError) -> Self {
+        Error::ParseError(format!("{}", value))
+    }
+}
+
+impl From<std::time::TryFromFloatSecsError> for Error {
+    fn from(value: std::time::TryFromFloatSecsError) -> Self {
+        Error::ParseError(format!("{}", value))
+    }
+}
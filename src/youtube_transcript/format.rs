@@ -0,0 +1,242 @@
+use crate::youtube_transcript::error::Error;
+use crate::youtube_transcript::parser::TranscriptCore;
+use roxmltree::{Document, Node};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Format of a caption track response, the `fmt` parameter of the track url.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionFormat {
+    /// `<transcript><text start dur>`, the default of the track url
+    Xml,
+    /// `<timedtext format="3"><body><p t d>` in milliseconds, `fmt=srv3`.
+    /// Auto-generated tracks time every word with a nested `<s t>`, read
+    /// into [`TranscriptCore::words`].
+    Srv3,
+    /// `{"wireMagic": "pb3", "events": [{"tStartMs", "dDurationMs", "segs"}]}`,
+    /// `fmt=json3`
+    Json3,
+    /// WebVTT cues, `fmt=vtt`
+    Vtt,
+}
+
+impl CaptionFormat {
+    /// format of a caption track response, `None` for an unknown format.
+    pub fn detect(text: &str) -> Option<Self> {
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        if text.starts_with("WEBVTT") {
+            Some(CaptionFormat::Vtt)
+        } else if text.starts_with('{') {
+            json3(text).ok().map(|_| CaptionFormat::Json3)
+        } else if text.starts_with('<') {
+            let doc = Document::parse(text).ok()?;
+            match doc.root_element().tag_name().name() {
+                "transcript" => Some(CaptionFormat::Xml),
+                "timedtext" => Some(CaptionFormat::Srv3),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// segments of a caption track response in this format. Segments
+    /// without text are left out.
+    pub fn parse(self, text: &str) -> Result<Vec<TranscriptCore>, Error> {
+        let text = text.trim_start_matches('\u{feff}');
+        let segments = match self {
+            CaptionFormat::Xml => parse_xml(&Document::parse(text)?)?,
+            CaptionFormat::Srv3 => parse_srv3(&Document::parse(text)?)?,
+            CaptionFormat::Json3 => parse_json3(text)?,
+            CaptionFormat::Vtt => parse_vtt(text)?,
+        };
+        Ok(segments
+            .into_iter()
+            .filter(|x| !x.text.is_empty())
+            .collect())
+    }
+}
+
+fn parse_xml(doc: &Document) -> Result<Vec<TranscriptCore>, Error> {
+    doc.descendants()
+        .filter(|x| x.has_tag_name("text"))
+        .map(|node| {
+            Ok(TranscriptCore {
+                text: node_text(&node),
+                // 음수, NaN 같은 시간은 패닉 대신 읽기 오류로 돌려준다.
+                start: Duration::try_from_secs_f32(attribute(&node, "start")?.parse()?)?,
+                duration: Duration::try_from_secs_f32(attribute(&node, "dur")?.parse()?)?,
+                words: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+fn parse_srv3(doc: &Document) -> Result<Vec<TranscriptCore>, Error> {
+    doc.descendants()
+        .filter(|x| x.has_tag_name("p"))
+        .map(|node| {
+            // 단어마다 <s> 로 나뉜 문장은 이어 붙이고, 단어의 시작은 따로 둔다.
+            let start = Duration::from_millis(millis(attribute(&node, "t")?)?);
+            let mut words = node
+                .children()
+                .filter(|x| x.has_tag_name("s"))
+                .map(|word| {
+                    // 단어의 t 는 문장의 시작에서부터 잰다.
+                    let offset = word.attribute("t").map_or(Ok(0), millis)?;
+                    Ok((start + Duration::from_millis(offset), node_text(&word)))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            words.retain(|(_, text)| !text.is_empty());
+            Ok(TranscriptCore {
+                text: node_text(&node),
+                start,
+                duration: Duration::from_millis(node.attribute("d").map_or(Ok(0), millis)?),
+                words,
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct Json3 {
+    #[serde(rename(deserialize = "wireMagic"))]
+    wire_magic: Option<String>,
+    events: Option<Vec<Json3Event>>,
+}
+
+#[derive(Deserialize)]
+struct Json3Event {
+    #[serde(rename(deserialize = "tStartMs"))]
+    start: u64,
+    #[serde(rename(deserialize = "dDurationMs"), default)]
+    duration: u64,
+    /// missing on the events that only set the window of the captions
+    #[serde(default)]
+    segs: Vec<Json3Segment>,
+}
+
+#[derive(Deserialize)]
+struct Json3Segment {
+    #[serde(default)]
+    utf8: String,
+}
+
+/// reads a json3 track, any other json (Ex: an error body) failing.
+fn json3(text: &str) -> Result<Json3, Error> {
+    let json3: Json3 =
+        serde_json::from_str(text).map_err(|x| Error::ParseError(format!("{}", x)))?;
+    if json3.wire_magic.is_none() && json3.events.is_none() {
+        return Err(Error::ParseError("not a json3 caption track".to_owned()));
+    }
+    Ok(json3)
+}
+
+fn parse_json3(text: &str) -> Result<Vec<TranscriptCore>, Error> {
+    Ok(json3(text)?
+        .events
+        .unwrap_or_default()
+        .into_iter()
+        .map(|event| TranscriptCore {
+            text: event
+                .segs
+                .iter()
+                .map(|x| x.utf8.as_str())
+                .collect::<String>()
+                .trim()
+                .to_owned(),
+            start: Duration::from_millis(event.start),
+            duration: Duration::from_millis(event.duration),
+            words: Vec::new(),
+        })
+        .collect())
+}
+
+fn parse_vtt(text: &str) -> Result<Vec<TranscriptCore>, Error> {
+    let mut segments = Vec::new();
+    let mut previous: Vec<String> = Vec::new();
+    // 빈 줄로 나뉜 블록 중 첫 블록은 헤더다.
+    for block in text.replace("\r\n", "\n").split("\n\n").skip(1) {
+        let mut lines = block.lines().skip_while(|x| !x.contains("-->"));
+        let Some(timing) = lines.next() else {
+            continue;
+        };
+        let (start, end) = timing
+            .split_once("-->")
+            .ok_or_else(|| Error::ParseError(format!("vtt cue timing: {}", timing)))?;
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let (start, end) = (timestamp(start.trim())?, timestamp(end)?);
+
+        let cue: Vec<String> = lines
+            .map(|x| decode_entities(&strip_tags(x)).trim().to_owned())
+            .filter(|x| !x.is_empty())
+            .collect();
+        // 자동 생성 자막은 앞 큐의 마지막 줄을 첫 줄로 다시 보여주므로 빼고 읽는다.
+        let rolled = usize::from(!cue.is_empty() && previous.last() == cue.first());
+        let text = cue[rolled..].join("\n");
+        previous = cue;
+        segments.push(TranscriptCore {
+            text,
+            start,
+            duration: end.saturating_sub(start),
+            words: Vec::new(),
+        });
+    }
+    Ok(segments)
+}
+
+/// text of `node` and every node under it.
+fn node_text(node: &Node) -> String {
+    node.descendants()
+        .filter(|x| x.is_text())
+        .filter_map(|x| x.text())
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+fn attribute<'a>(node: &Node<'a, '_>, name: &str) -> Result<&'a str, Error> {
+    node.attribute(name)
+        .ok_or_else(|| Error::ParseError(format!("transcript parse error: no {}", name)))
+}
+
+fn millis(value: &str) -> Result<u64, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::ParseError(format!("transcript parse error: {}", value)))
+}
+
+/// Ex: "01:02.500", "1:01:02.500"
+fn timestamp(value: &str) -> Result<Duration, Error> {
+    let seconds = value.split(':').try_fold(0f32, |seconds, x| {
+        let x = x.parse::<f32>()?;
+        // "-00:01.000" 의 부호는 더하면서 사라지므로 자리마다 본다.
+        if x.is_sign_negative() {
+            return Err(Error::ParseError(format!("negative timestamp: {}", value)));
+        }
+        Ok(seconds * 60.0 + x)
+    })?;
+    Ok(Duration::try_from_secs_f32(seconds)?)
+}
+
+/// drops the word timings and styles of a cue line. Ex: "<00:00:01.000><c> word</c>"
+fn strip_tags(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
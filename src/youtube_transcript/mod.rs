@@ -1,6 +1,7 @@
 // https://github.com/akhildevelops/summarizer/tree/main/youtube-transcript
 pub mod config;
 pub mod error;
+pub mod format;
 pub mod parser;
 pub mod utils;
 pub mod youtube;
@@ -1,31 +1,97 @@
 use crate::youtube_transcript::error;
+use crate::youtube_transcript::format::CaptionFormat;
 use crate::youtube_transcript::utils::to_human_readable;
-use roxmltree::Document;
 use serde;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json;
-use std::error::Error;
 use std::time::Duration;
 
-#[derive(Default, Deserialize)]
-pub(crate) struct Caption {
-    #[serde(rename(deserialize = "baseUrl"))]
+/// Caption track listed in youtube's `captionTracks`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Caption {
+    #[serde(rename(deserialize = "baseUrl"), skip_serializing)]
     pub base_url: String,
+    /// language of the track. Ex: "ko", "en-US"
     #[serde(rename(deserialize = "languageCode"))]
     pub lang_code: String,
+    /// kind of the track, `Some("asr")` for auto-generated captions.
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+impl Caption {
+    /// whether the track is generated by youtube's speech recognition.
+    pub fn is_auto_generated(&self) -> bool {
+        self.kind.as_deref() == Some("asr")
+    }
+
+    fn matches(&self, lang: &str) -> bool {
+        self.lang_code == lang
+            || self
+                .lang_code
+                .strip_prefix(lang)
+                .is_some_and(|x| x.starts_with('-'))
+    }
 }
 
 #[derive(Deserialize)]
 struct Captions {
-    #[serde(rename(deserialize = "captionTracks"))]
+    #[serde(rename(deserialize = "captionTracks"), default)]
     caption_tracks: Vec<Caption>,
 }
 
+#[derive(Deserialize)]
+struct PlayerResponse {
+    #[serde(rename(deserialize = "playabilityStatus"))]
+    playability_status: Option<PlayabilityStatus>,
+    captions: Option<PlayerCaptions>,
+}
+
+#[derive(Deserialize)]
+struct PlayabilityStatus {
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct PlayerCaptions {
+    #[serde(rename(deserialize = "playerCaptionsTracklistRenderer"))]
+    renderer: Captions,
+}
+
+/// caption tracks of a `youtubei/v1/player` response, none for a playable
+/// video without captions.
+pub(crate) fn player_captions(json: &str) -> Result<Vec<Caption>, error::Error> {
+    let response: PlayerResponse =
+        serde_json::from_str(json).map_err(|x| error::Error::ParseError(format!("{}", x)))?;
+    match response
+        .playability_status
+        .as_ref()
+        .map(|x| x.status.as_str())
+    {
+        Some("OK") => {}
+        Some(status) => {
+            return Err(error::Error::Unavailable(format!(
+                "video is not playable: {}",
+                status
+            )))
+        }
+        None => {
+            return Err(error::Error::Unavailable(
+                "no playability status".to_owned(),
+            ))
+        }
+    }
+    Ok(response
+        .captions
+        .map(|x| x.renderer.caption_tracks)
+        .unwrap_or_default())
+}
+
 pub(crate) trait HTMLParser<'a> {
     fn html_string(&'a self) -> &'a str;
 
-    fn caption(&'a self, from: &str, to: &str) -> Result<Caption, error::Error> {
+    fn captions(&'a self, from: &str, to: &str) -> Result<Vec<Caption>, error::Error> {
         let html = self.html_string();
         let start = html
             .split_once(from)
@@ -37,16 +103,33 @@ pub(crate) trait HTMLParser<'a> {
             .0;
         let value: Captions = serde_json::from_str(actual_json)
             .map_err(|x| error::Error::ParseError(format!("{}", x)))?;
-        let caption = value
-            .caption_tracks
-            .into_iter()
-            .filter(|x| x.lang_code == "ko")
-            .next()
-            .unwrap_or_default();
-        Ok(caption)
+        Ok(value.caption_tracks)
+    }
+
+    /// status of the video in the player response. Ex: "OK", "LOGIN_REQUIRED"
+    fn playability_status(&'a self, from: &str) -> Option<&'a str> {
+        let start = self.html_string().split_once(from)?.1;
+        Some(start.split_once('"')?.0)
     }
 }
 
+/// picks the track of the first available language in `languages`, preferring
+/// manually written captions over auto-generated ones of the same language.
+pub(crate) fn select_caption<'c>(
+    captions: &'c [Caption],
+    languages: &[String],
+) -> Option<&'c Caption> {
+    languages.iter().find_map(|lang| {
+        let mut tracks = captions.iter().filter(|x| x.matches(lang));
+        let first = tracks.next()?;
+        if first.is_auto_generated() {
+            tracks.find(|x| !x.is_auto_generated()).or(Some(first))
+        } else {
+            Some(first)
+        }
+    })
+}
+
 impl<'a> HTMLParser<'a> for String {
     fn html_string(&'a self) -> &'a str {
         self.as_str()
@@ -68,13 +151,30 @@ pub struct TranscriptCore {
     pub start: Duration,
     /// duration of the text Ex: "0.8 sec"
     pub duration: Duration,
+    /// start of every word in the whole video, for the srv3 tracks timing
+    /// them. Not kept in the store.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<(Duration, String)>,
 }
 
 /// Struct containing youtube's transcript data as a Vec<[`TranscriptCore`]>
-#[derive(Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct Transcript {
     /// List of transcript texts in [`TranscriptCore`] format
     pub transcripts: Vec<TranscriptCore>,
+    /// caption track the transcript was read from, `None` if no track matched
+    pub caption: Option<Caption>,
+}
+
+impl Transcript {
+    /// transcript texts joined into a single script.
+    pub fn script(&self) -> String {
+        self.transcripts
+            .iter()
+            .map(|x| x.text.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
 }
 
 impl IntoIterator for Transcript {
@@ -108,35 +208,14 @@ impl From<Transcript> for String {
 pub(crate) struct TranscriptParser;
 
 impl TranscriptParser {
-    pub fn parse<'input>(
-        transcript: &'input Document<'input>,
-    ) -> Result<Transcript, Box<dyn Error>> {
-        let mut transcripts = Vec::new();
-        let nodes = transcript
-            .descendants()
-            .filter(|x| x.tag_name() == "text".into());
-        for node in nodes {
-            let start = node
-                .attribute("start")
-                .ok_or(error::Error::ParseError("transcript parse error".into()))?
-                .parse::<f32>()?;
-            let duration = node
-                .attribute("dur")
-                .ok_or(error::Error::ParseError("transcript parse error".into()))?
-                .parse::<f32>()?;
-            let node = node
-                .last_child()
-                .ok_or(error::Error::ParseError("transcript parse error".into()))?;
-            let text = node
-                .text()
-                .ok_or(error::Error::ParseError("transcript error".into()))?;
-
-            transcripts.push(TranscriptCore {
-                text: text.into(),
-                start: Duration::from_secs_f32(start),
-                duration: Duration::from_secs_f32(duration),
-            })
-        }
-        Ok(Transcript { transcripts })
+    /// reads a caption track response in any [`CaptionFormat`], detected
+    /// from the response itself.
+    pub fn parse(transcript: &str) -> Result<Transcript, error::Error> {
+        let format = CaptionFormat::detect(transcript)
+            .ok_or_else(|| error::Error::ParseError("unknown caption format".into()))?;
+        Ok(Transcript {
+            transcripts: format.parse(transcript)?,
+            caption: None,
+        })
     }
 }
@@ -1,57 +1,270 @@
-use crate::youtube_transcript::config::{Config, CONFIG_VAL};
-use crate::youtube_transcript::parser::{HTMLParser, Transcript, TranscriptParser};
-use reqwest::Client;
-use roxmltree::Document;
-use std::error::Error;
+use crate::cache::{self, Cache};
+use crate::rate_limit::RateLimiter;
+use crate::youtube_transcript::config::{self, CaptionSource, Config};
+use crate::youtube_transcript::error::Error;
+use crate::youtube_transcript::parser::{
+    player_captions, select_caption, Caption, HTMLParser, Transcript, TranscriptParser,
+};
+use reqwest::{Client, Url};
+use serde_json::{json, Value};
 
-/// Youtube container that holds the [`Config`].
+pub const BASE_URL: &str = "https://www.youtube.com";
+
+/// site of the watch pages in `YOUTUBE_URL`, [`BASE_URL`] by default.
+pub fn base_url_from_env() -> String {
+    std::env::var("YOUTUBE_URL").unwrap_or_else(|_| BASE_URL.to_owned())
+}
+
+/// Youtube container that holds the [`Config`], the preferred caption languages,
+/// the cache of the fetched pages and the rate limits of the site.
 pub struct Youtube<'b> {
     config: &'b Config,
+    languages: &'b [String],
+    cache: &'b Cache,
+    rate_limiter: &'b RateLimiter,
+    base_url: &'b str,
 }
 
 impl<'b> Youtube<'b> {
+    /// link to the watch page of a video.
+    pub fn watch_url(&self, video_id: &str) -> String {
+        format!("{}/watch?v={}", self.base_url, video_id)
+    }
     /// extracts [`Transcript`] from the video link provided.
-    pub async fn transcript<'a>(&self, url: &'a str) -> Result<Transcript, Box<dyn Error>> {
-        let client = Client::default();
-        let response = client.get(url).send().await?;
-        let text = response.text().await?;
-        self.transcript_from_text(&text).await
+    pub async fn transcript<'a>(&self, url: &'a str) -> Result<Transcript, Error> {
+        let captions = self.caption_tracks(url).await?;
+        self.transcript_from_captions(&captions).await
     }
     /// extracts [`Transcript`] from the youtube raw html text provided.
-    pub async fn transcript_from_text(&self, text: &str) -> Result<Transcript, Box<dyn Error>> {
-        let client = Client::default();
-        let c = text.caption(self.config.parser.from, self.config.parser.to)?;
-        if c.base_url.is_empty() {
-            Ok(Transcript {
-                transcripts: Vec::new(),
-            })
-        } else {
-            let response = client.get(c.base_url).send().await?;
-            let trans_resp = response.text().await?;
-            let doc = Document::parse(&trans_resp)?;
-            let t = TranscriptParser::parse(&doc)?;
-            Ok(t)
+    pub async fn transcript_from_text(&self, text: &str) -> Result<Transcript, Error> {
+        let captions = self.caption_tracks_from_text(text)?;
+        self.transcript_from_captions(&captions).await
+    }
+    /// lists every caption track of the video link provided, from the player
+    /// api or the watch page as the [`Config`] selects. The watch page is
+    /// read when the player api fails.
+    pub async fn caption_tracks(&self, url: &str) -> Result<Vec<Caption>, Error> {
+        if self.config.source == CaptionSource::InnerTube {
+            if let Some(video_id) = video_id(url) {
+                // 플레이어 api 가 막히면 시청 페이지로 넘어간다.
+                if let Ok(captions) = self.player_caption_tracks(&video_id).await {
+                    return Ok(captions);
+                }
+            }
         }
+        // 동의, 봇 확인 페이지 등 자막 목록을 읽지 못한 페이지는 저장하지 않는다.
+        self.cache
+            .get_or_fetch_parsed(
+                "watch_page",
+                url,
+                cache::TTL_WATCH_PAGE,
+                || self.fetch(url),
+                |text: &String| self.caption_tracks_from_text(text),
+            )
+            .await
+    }
+    /// lists every caption track of a video from the `youtubei/v1/player`
+    /// api, none for a playable video without captions.
+    pub async fn player_caption_tracks(&self, video_id: &str) -> Result<Vec<Caption>, Error> {
+        let url = format!("{}/youtubei/v1/player?prettyPrint=false", self.base_url);
+        let client = &self.config.innertube;
+        let body = json!({
+            "context": {
+                "client": {
+                    "clientName": client.client_name,
+                    "clientVersion": client.client_version,
+                },
+            },
+            "videoId": video_id,
+        });
+        // 같은 비디오도 클라이언트마다 응답이 다르다.
+        let key = Cache::key(&[
+            self.base_url,
+            client.client_name,
+            client.client_version,
+            video_id,
+        ]);
+        // 재생할 수 없다는 응답은 잠깐일 수 있으니 저장하지 않는다.
+        self.cache
+            .get_or_fetch_parsed(
+                "player",
+                &key,
+                cache::TTL_WATCH_PAGE,
+                || self.fetch_player(&url, &body),
+                |text: &String| player_captions(text),
+            )
+            .await
+    }
+    /// lists every caption track in the youtube raw html text provided, none
+    /// for a playable video without captions.
+    pub fn caption_tracks_from_text(&self, text: &str) -> Result<Vec<Caption>, Error> {
+        let parser = &self.config.parser;
+        if text.contains(parser.from) {
+            return text.captions(parser.from, parser.to);
+        }
+        // 자막이 하나도 없는 비디오는 시청 페이지에 자막 목록이 없다.
+        match text.playability_status(parser.playability) {
+            Some("OK") => Ok(Vec::new()),
+            Some(status) => Err(Error::Unavailable(format!(
+                "video is not playable: {}",
+                status
+            ))),
+            None => Err(Error::Unavailable("not a watch page".to_owned())),
+        }
+    }
+    async fn transcript_from_captions(&self, captions: &[Caption]) -> Result<Transcript, Error> {
+        match select_caption(captions, self.languages) {
+            Some(c) => self.transcript_from_caption(c).await,
+            None => Ok(Transcript::default()),
+        }
+    }
+    /// extracts [`Transcript`] from the given caption track.
+    pub async fn transcript_from_caption(&self, caption: &Caption) -> Result<Transcript, Error> {
+        let url = self.rebase(&caption.base_url);
+        // 비었거나 잘린 응답은 30일 동안 남지 않도록 읽은 뒤에 저장한다.
+        let mut t = self
+            .cache
+            .get_or_fetch_parsed(
+                "timedtext",
+                &caption_key(caption),
+                cache::TTL_TRANSCRIPT,
+                || self.fetch(&url),
+                |text: &String| TranscriptParser::parse(text),
+            )
+            .await?;
+        t.caption = Some(caption.clone());
+        Ok(t)
+    }
+
+    /// the watch page lists the captions under youtube.com, they are fetched
+    /// from the same site as the page.
+    fn rebase(&self, url: &str) -> String {
+        match url.strip_prefix(BASE_URL) {
+            Some(path) => format!("{}{}", self.base_url, path),
+            None => url.to_owned(),
+        }
+    }
+
+    async fn fetch(&self, url: &str) -> Result<String, Error> {
+        self.rate_limiter.wait(url).await;
+        let client = Client::default();
+        let response = client.get(url).send().await?.error_for_status()?;
+        Ok(response.text().await?)
+    }
+
+    async fn fetch_player(&self, url: &str, body: &Value) -> Result<String, Error> {
+        self.rate_limiter.wait(url).await;
+        let client = Client::default();
+        let response = client
+            .post(url)
+            .json(body)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.text().await?)
     }
 }
 
+/// id of the video of a watch page link. Ex: "https://www.youtube.com/watch?v=ID"
+fn video_id(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()?
+        .query_pairs()
+        .find(|(name, _)| name == "v")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// the signature and expiry of a caption url change on every watch page, so
+/// only the parameters naming the track and its format make the key.
+fn caption_key(caption: &Caption) -> String {
+    let Ok(url) = Url::parse(&caption.base_url) else {
+        return caption.base_url.clone();
+    };
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| {
+            matches!(
+                name.as_ref(),
+                "v" | "lang" | "kind" | "name" | "tlang" | "fmt"
+            )
+        })
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+    params
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 /// Builder struct for building [`Youtube`]
 pub struct YoutubeBuilder<'b> {
     config: &'b Config,
+    languages: Vec<String>,
+    cache: Cache,
+    rate_limiter: RateLimiter,
+    base_url: String,
 }
 
 impl<'b> YoutubeBuilder<'b> {
-    /// creates [`YoutubeBuilder`] with default [`Config`] values.
+    /// creates [`YoutubeBuilder`] with the [`Config`] given to [`config::init`],
+    /// the one of the environment in the binary, default values otherwise.
+    /// Prefers korean captions.
     pub fn default() -> Self {
         Self {
-            config: &CONFIG_VAL,
+            config: config::current(),
+            languages: vec!["ko".to_owned()],
+            cache: Cache::disabled(),
+            rate_limiter: RateLimiter::default(),
+            base_url: BASE_URL.to_owned(),
         }
     }
 
+    /// reads the captions as `config` sets, [`config::current`] by default.
+    pub fn config(mut self, config: &'b Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// fetches the pages from `base_url` instead of youtube.com.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    /// keeps the watch pages, the player responses and the caption tracks
+    /// in `cache`.
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// spaces out the fetches with `rate_limiter`, shared with other fetches
+    /// of the same site.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// sets the caption languages to try, in order of preference. Ex: `["ko", "en"]`
+    pub fn languages<I, S>(mut self, languages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.languages = languages.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Builds [`Youtube`]
     pub fn build(&'b self) -> Youtube<'b> {
         Youtube {
             config: self.config,
+            languages: &self.languages,
+            cache: &self.cache,
+            rate_limiter: &self.rate_limiter,
+            base_url: &self.base_url,
         }
     }
 }